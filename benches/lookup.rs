@@ -0,0 +1,311 @@
+//! Compares the `lookup` strategies of `from_repr` on 600 variant IntType enums and a
+//! 48 range IntRange enum, run with `cargo bench --bench lookup`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use inttype_enum::{IntRange, IntType};
+
+/// Defines the same enum once for every `lookup`
+macro_rules! codes {
+    ($($name: ident = $lookup: literal),*; $variants: tt) => {$(
+        #[allow(dead_code)]
+        #[derive(Debug, IntType)]
+        #[repr(u16)]
+        #[inttype(lookup = $lookup)]
+        enum $name $variants
+    )*};
+}
+
+// every code from 1000 to 1699 but the multiples of 7
+codes! {
+    DenseMatch = "match", DenseTable = "table", DenseSearch = "search"; {
+        D0 = 1000, D1 = 1002, D2 = 1003, D3 = 1004, D4 = 1005, D5 = 1006, D6 = 1007, D7 = 1009,
+        D8 = 1010, D9 = 1011, D10 = 1012, D11 = 1013, D12 = 1014, D13 = 1016, D14 = 1017,
+        D15 = 1018, D16 = 1019, D17 = 1020, D18 = 1021, D19 = 1023, D20 = 1024, D21 = 1025,
+        D22 = 1026, D23 = 1027, D24 = 1028, D25 = 1030, D26 = 1031, D27 = 1032, D28 = 1033,
+        D29 = 1034, D30 = 1035, D31 = 1037, D32 = 1038, D33 = 1039, D34 = 1040, D35 = 1041,
+        D36 = 1042, D37 = 1044, D38 = 1045, D39 = 1046, D40 = 1047, D41 = 1048, D42 = 1049,
+        D43 = 1051, D44 = 1052, D45 = 1053, D46 = 1054, D47 = 1055, D48 = 1056, D49 = 1058,
+        D50 = 1059, D51 = 1060, D52 = 1061, D53 = 1062, D54 = 1063, D55 = 1065, D56 = 1066,
+        D57 = 1067, D58 = 1068, D59 = 1069, D60 = 1070, D61 = 1072, D62 = 1073, D63 = 1074,
+        D64 = 1075, D65 = 1076, D66 = 1077, D67 = 1079, D68 = 1080, D69 = 1081, D70 = 1082,
+        D71 = 1083, D72 = 1084, D73 = 1086, D74 = 1087, D75 = 1088, D76 = 1089, D77 = 1090,
+        D78 = 1091, D79 = 1093, D80 = 1094, D81 = 1095, D82 = 1096, D83 = 1097, D84 = 1098,
+        D85 = 1100, D86 = 1101, D87 = 1102, D88 = 1103, D89 = 1104, D90 = 1105, D91 = 1107,
+        D92 = 1108, D93 = 1109, D94 = 1110, D95 = 1111, D96 = 1112, D97 = 1114, D98 = 1115,
+        D99 = 1116, D100 = 1117, D101 = 1118, D102 = 1119, D103 = 1121, D104 = 1122, D105 = 1123,
+        D106 = 1124, D107 = 1125, D108 = 1126, D109 = 1128, D110 = 1129, D111 = 1130, D112 = 1131,
+        D113 = 1132, D114 = 1133, D115 = 1135, D116 = 1136, D117 = 1137, D118 = 1138, D119 = 1139,
+        D120 = 1140, D121 = 1142, D122 = 1143, D123 = 1144, D124 = 1145, D125 = 1146, D126 = 1147,
+        D127 = 1149, D128 = 1150, D129 = 1151, D130 = 1152, D131 = 1153, D132 = 1154, D133 = 1156,
+        D134 = 1157, D135 = 1158, D136 = 1159, D137 = 1160, D138 = 1161, D139 = 1163, D140 = 1164,
+        D141 = 1165, D142 = 1166, D143 = 1167, D144 = 1168, D145 = 1170, D146 = 1171, D147 = 1172,
+        D148 = 1173, D149 = 1174, D150 = 1175, D151 = 1177, D152 = 1178, D153 = 1179, D154 = 1180,
+        D155 = 1181, D156 = 1182, D157 = 1184, D158 = 1185, D159 = 1186, D160 = 1187, D161 = 1188,
+        D162 = 1189, D163 = 1191, D164 = 1192, D165 = 1193, D166 = 1194, D167 = 1195, D168 = 1196,
+        D169 = 1198, D170 = 1199, D171 = 1200, D172 = 1201, D173 = 1202, D174 = 1203, D175 = 1205,
+        D176 = 1206, D177 = 1207, D178 = 1208, D179 = 1209, D180 = 1210, D181 = 1212, D182 = 1213,
+        D183 = 1214, D184 = 1215, D185 = 1216, D186 = 1217, D187 = 1219, D188 = 1220, D189 = 1221,
+        D190 = 1222, D191 = 1223, D192 = 1224, D193 = 1226, D194 = 1227, D195 = 1228, D196 = 1229,
+        D197 = 1230, D198 = 1231, D199 = 1233, D200 = 1234, D201 = 1235, D202 = 1236, D203 = 1237,
+        D204 = 1238, D205 = 1240, D206 = 1241, D207 = 1242, D208 = 1243, D209 = 1244, D210 = 1245,
+        D211 = 1247, D212 = 1248, D213 = 1249, D214 = 1250, D215 = 1251, D216 = 1252, D217 = 1254,
+        D218 = 1255, D219 = 1256, D220 = 1257, D221 = 1258, D222 = 1259, D223 = 1261, D224 = 1262,
+        D225 = 1263, D226 = 1264, D227 = 1265, D228 = 1266, D229 = 1268, D230 = 1269, D231 = 1270,
+        D232 = 1271, D233 = 1272, D234 = 1273, D235 = 1275, D236 = 1276, D237 = 1277, D238 = 1278,
+        D239 = 1279, D240 = 1280, D241 = 1282, D242 = 1283, D243 = 1284, D244 = 1285, D245 = 1286,
+        D246 = 1287, D247 = 1289, D248 = 1290, D249 = 1291, D250 = 1292, D251 = 1293, D252 = 1294,
+        D253 = 1296, D254 = 1297, D255 = 1298, D256 = 1299, D257 = 1300, D258 = 1301, D259 = 1303,
+        D260 = 1304, D261 = 1305, D262 = 1306, D263 = 1307, D264 = 1308, D265 = 1310, D266 = 1311,
+        D267 = 1312, D268 = 1313, D269 = 1314, D270 = 1315, D271 = 1317, D272 = 1318, D273 = 1319,
+        D274 = 1320, D275 = 1321, D276 = 1322, D277 = 1324, D278 = 1325, D279 = 1326, D280 = 1327,
+        D281 = 1328, D282 = 1329, D283 = 1331, D284 = 1332, D285 = 1333, D286 = 1334, D287 = 1335,
+        D288 = 1336, D289 = 1338, D290 = 1339, D291 = 1340, D292 = 1341, D293 = 1342, D294 = 1343,
+        D295 = 1345, D296 = 1346, D297 = 1347, D298 = 1348, D299 = 1349, D300 = 1350, D301 = 1352,
+        D302 = 1353, D303 = 1354, D304 = 1355, D305 = 1356, D306 = 1357, D307 = 1359, D308 = 1360,
+        D309 = 1361, D310 = 1362, D311 = 1363, D312 = 1364, D313 = 1366, D314 = 1367, D315 = 1368,
+        D316 = 1369, D317 = 1370, D318 = 1371, D319 = 1373, D320 = 1374, D321 = 1375, D322 = 1376,
+        D323 = 1377, D324 = 1378, D325 = 1380, D326 = 1381, D327 = 1382, D328 = 1383, D329 = 1384,
+        D330 = 1385, D331 = 1387, D332 = 1388, D333 = 1389, D334 = 1390, D335 = 1391, D336 = 1392,
+        D337 = 1394, D338 = 1395, D339 = 1396, D340 = 1397, D341 = 1398, D342 = 1399, D343 = 1401,
+        D344 = 1402, D345 = 1403, D346 = 1404, D347 = 1405, D348 = 1406, D349 = 1408, D350 = 1409,
+        D351 = 1410, D352 = 1411, D353 = 1412, D354 = 1413, D355 = 1415, D356 = 1416, D357 = 1417,
+        D358 = 1418, D359 = 1419, D360 = 1420, D361 = 1422, D362 = 1423, D363 = 1424, D364 = 1425,
+        D365 = 1426, D366 = 1427, D367 = 1429, D368 = 1430, D369 = 1431, D370 = 1432, D371 = 1433,
+        D372 = 1434, D373 = 1436, D374 = 1437, D375 = 1438, D376 = 1439, D377 = 1440, D378 = 1441,
+        D379 = 1443, D380 = 1444, D381 = 1445, D382 = 1446, D383 = 1447, D384 = 1448, D385 = 1450,
+        D386 = 1451, D387 = 1452, D388 = 1453, D389 = 1454, D390 = 1455, D391 = 1457, D392 = 1458,
+        D393 = 1459, D394 = 1460, D395 = 1461, D396 = 1462, D397 = 1464, D398 = 1465, D399 = 1466,
+        D400 = 1467, D401 = 1468, D402 = 1469, D403 = 1471, D404 = 1472, D405 = 1473, D406 = 1474,
+        D407 = 1475, D408 = 1476, D409 = 1478, D410 = 1479, D411 = 1480, D412 = 1481, D413 = 1482,
+        D414 = 1483, D415 = 1485, D416 = 1486, D417 = 1487, D418 = 1488, D419 = 1489, D420 = 1490,
+        D421 = 1492, D422 = 1493, D423 = 1494, D424 = 1495, D425 = 1496, D426 = 1497, D427 = 1499,
+        D428 = 1500, D429 = 1501, D430 = 1502, D431 = 1503, D432 = 1504, D433 = 1506, D434 = 1507,
+        D435 = 1508, D436 = 1509, D437 = 1510, D438 = 1511, D439 = 1513, D440 = 1514, D441 = 1515,
+        D442 = 1516, D443 = 1517, D444 = 1518, D445 = 1520, D446 = 1521, D447 = 1522, D448 = 1523,
+        D449 = 1524, D450 = 1525, D451 = 1527, D452 = 1528, D453 = 1529, D454 = 1530, D455 = 1531,
+        D456 = 1532, D457 = 1534, D458 = 1535, D459 = 1536, D460 = 1537, D461 = 1538, D462 = 1539,
+        D463 = 1541, D464 = 1542, D465 = 1543, D466 = 1544, D467 = 1545, D468 = 1546, D469 = 1548,
+        D470 = 1549, D471 = 1550, D472 = 1551, D473 = 1552, D474 = 1553, D475 = 1555, D476 = 1556,
+        D477 = 1557, D478 = 1558, D479 = 1559, D480 = 1560, D481 = 1562, D482 = 1563, D483 = 1564,
+        D484 = 1565, D485 = 1566, D486 = 1567, D487 = 1569, D488 = 1570, D489 = 1571, D490 = 1572,
+        D491 = 1573, D492 = 1574, D493 = 1576, D494 = 1577, D495 = 1578, D496 = 1579, D497 = 1580,
+        D498 = 1581, D499 = 1583, D500 = 1584, D501 = 1585, D502 = 1586, D503 = 1587, D504 = 1588,
+        D505 = 1590, D506 = 1591, D507 = 1592, D508 = 1593, D509 = 1594, D510 = 1595, D511 = 1597,
+        D512 = 1598, D513 = 1599, D514 = 1600, D515 = 1601, D516 = 1602, D517 = 1604, D518 = 1605,
+        D519 = 1606, D520 = 1607, D521 = 1608, D522 = 1609, D523 = 1611, D524 = 1612, D525 = 1613,
+        D526 = 1614, D527 = 1615, D528 = 1616, D529 = 1618, D530 = 1619, D531 = 1620, D532 = 1621,
+        D533 = 1622, D534 = 1623, D535 = 1625, D536 = 1626, D537 = 1627, D538 = 1628, D539 = 1629,
+        D540 = 1630, D541 = 1632, D542 = 1633, D543 = 1634, D544 = 1635, D545 = 1636, D546 = 1637,
+        D547 = 1639, D548 = 1640, D549 = 1641, D550 = 1642, D551 = 1643, D552 = 1644, D553 = 1646,
+        D554 = 1647, D555 = 1648, D556 = 1649, D557 = 1650, D558 = 1651, D559 = 1653, D560 = 1654,
+        D561 = 1655, D562 = 1656, D563 = 1657, D564 = 1658, D565 = 1660, D566 = 1661, D567 = 1662,
+        D568 = 1663, D569 = 1664, D570 = 1665, D571 = 1667, D572 = 1668, D573 = 1669, D574 = 1670,
+        D575 = 1671, D576 = 1672, D577 = 1674, D578 = 1675, D579 = 1676, D580 = 1677, D581 = 1678,
+        D582 = 1679, D583 = 1681, D584 = 1682, D585 = 1683, D586 = 1684, D587 = 1685, D588 = 1686,
+        D589 = 1688, D590 = 1689, D591 = 1690, D592 = 1691, D593 = 1692, D594 = 1693, D595 = 1695,
+        D596 = 1696, D597 = 1697, D598 = 1698, D599 = 1699,
+    }
+}
+
+// 600 codes about 97 apart
+codes! {
+    SparseMatch = "match", SparseSearch = "search"; {
+        S0 = 0, S1 = 98, S2 = 198, S3 = 300, S4 = 391, S5 = 497, S6 = 592, S7 = 689, S8 = 788,
+        S9 = 876, S10 = 979, S11 = 1071, S12 = 1165, S13 = 1261, S14 = 1359, S15 = 1459,
+        S16 = 1561, S17 = 1652, S18 = 1758, S19 = 1853, S20 = 1950, S21 = 2049, S22 = 2137,
+        S23 = 2240, S24 = 2332, S25 = 2426, S26 = 2522, S27 = 2620, S28 = 2720, S29 = 2822,
+        S30 = 2913, S31 = 3019, S32 = 3114, S33 = 3211, S34 = 3310, S35 = 3398, S36 = 3501,
+        S37 = 3593, S38 = 3687, S39 = 3783, S40 = 3881, S41 = 3981, S42 = 4083, S43 = 4174,
+        S44 = 4280, S45 = 4375, S46 = 4472, S47 = 4571, S48 = 4659, S49 = 4762, S50 = 4854,
+        S51 = 4948, S52 = 5044, S53 = 5142, S54 = 5242, S55 = 5344, S56 = 5435, S57 = 5541,
+        S58 = 5636, S59 = 5733, S60 = 5832, S61 = 5920, S62 = 6023, S63 = 6115, S64 = 6209,
+        S65 = 6305, S66 = 6403, S67 = 6503, S68 = 6605, S69 = 6696, S70 = 6802, S71 = 6897,
+        S72 = 6994, S73 = 7093, S74 = 7181, S75 = 7284, S76 = 7376, S77 = 7470, S78 = 7566,
+        S79 = 7664, S80 = 7764, S81 = 7866, S82 = 7957, S83 = 8063, S84 = 8158, S85 = 8255,
+        S86 = 8354, S87 = 8442, S88 = 8545, S89 = 8637, S90 = 8731, S91 = 8827, S92 = 8925,
+        S93 = 9025, S94 = 9127, S95 = 9218, S96 = 9324, S97 = 9419, S98 = 9516, S99 = 9615,
+        S100 = 9703, S101 = 9806, S102 = 9898, S103 = 9992, S104 = 10088, S105 = 10186,
+        S106 = 10286, S107 = 10388, S108 = 10479, S109 = 10585, S110 = 10680, S111 = 10777,
+        S112 = 10876, S113 = 10964, S114 = 11067, S115 = 11159, S116 = 11253, S117 = 11349,
+        S118 = 11447, S119 = 11547, S120 = 11649, S121 = 11740, S122 = 11846, S123 = 11941,
+        S124 = 12038, S125 = 12137, S126 = 12225, S127 = 12328, S128 = 12420, S129 = 12514,
+        S130 = 12610, S131 = 12708, S132 = 12808, S133 = 12910, S134 = 13001, S135 = 13107,
+        S136 = 13202, S137 = 13299, S138 = 13398, S139 = 13486, S140 = 13589, S141 = 13681,
+        S142 = 13775, S143 = 13871, S144 = 13969, S145 = 14069, S146 = 14171, S147 = 14262,
+        S148 = 14368, S149 = 14463, S150 = 14560, S151 = 14659, S152 = 14747, S153 = 14850,
+        S154 = 14942, S155 = 15036, S156 = 15132, S157 = 15230, S158 = 15330, S159 = 15432,
+        S160 = 15523, S161 = 15629, S162 = 15724, S163 = 15821, S164 = 15920, S165 = 16008,
+        S166 = 16111, S167 = 16203, S168 = 16297, S169 = 16393, S170 = 16491, S171 = 16591,
+        S172 = 16693, S173 = 16784, S174 = 16890, S175 = 16985, S176 = 17082, S177 = 17181,
+        S178 = 17269, S179 = 17372, S180 = 17464, S181 = 17558, S182 = 17654, S183 = 17752,
+        S184 = 17852, S185 = 17954, S186 = 18045, S187 = 18151, S188 = 18246, S189 = 18343,
+        S190 = 18442, S191 = 18530, S192 = 18633, S193 = 18725, S194 = 18819, S195 = 18915,
+        S196 = 19013, S197 = 19113, S198 = 19215, S199 = 19306, S200 = 19412, S201 = 19507,
+        S202 = 19604, S203 = 19703, S204 = 19791, S205 = 19894, S206 = 19986, S207 = 20080,
+        S208 = 20176, S209 = 20274, S210 = 20374, S211 = 20476, S212 = 20567, S213 = 20673,
+        S214 = 20768, S215 = 20865, S216 = 20964, S217 = 21052, S218 = 21155, S219 = 21247,
+        S220 = 21341, S221 = 21437, S222 = 21535, S223 = 21635, S224 = 21737, S225 = 21828,
+        S226 = 21934, S227 = 22029, S228 = 22126, S229 = 22225, S230 = 22313, S231 = 22416,
+        S232 = 22508, S233 = 22602, S234 = 22698, S235 = 22796, S236 = 22896, S237 = 22998,
+        S238 = 23089, S239 = 23195, S240 = 23290, S241 = 23387, S242 = 23486, S243 = 23574,
+        S244 = 23677, S245 = 23769, S246 = 23863, S247 = 23959, S248 = 24057, S249 = 24157,
+        S250 = 24259, S251 = 24350, S252 = 24456, S253 = 24551, S254 = 24648, S255 = 24747,
+        S256 = 24835, S257 = 24938, S258 = 25030, S259 = 25124, S260 = 25220, S261 = 25318,
+        S262 = 25418, S263 = 25520, S264 = 25611, S265 = 25717, S266 = 25812, S267 = 25909,
+        S268 = 26008, S269 = 26096, S270 = 26199, S271 = 26291, S272 = 26385, S273 = 26481,
+        S274 = 26579, S275 = 26679, S276 = 26781, S277 = 26872, S278 = 26978, S279 = 27073,
+        S280 = 27170, S281 = 27269, S282 = 27357, S283 = 27460, S284 = 27552, S285 = 27646,
+        S286 = 27742, S287 = 27840, S288 = 27940, S289 = 28042, S290 = 28133, S291 = 28239,
+        S292 = 28334, S293 = 28431, S294 = 28530, S295 = 28618, S296 = 28721, S297 = 28813,
+        S298 = 28907, S299 = 29003, S300 = 29101, S301 = 29201, S302 = 29303, S303 = 29394,
+        S304 = 29500, S305 = 29595, S306 = 29692, S307 = 29791, S308 = 29879, S309 = 29982,
+        S310 = 30074, S311 = 30168, S312 = 30264, S313 = 30362, S314 = 30462, S315 = 30564,
+        S316 = 30655, S317 = 30761, S318 = 30856, S319 = 30953, S320 = 31052, S321 = 31140,
+        S322 = 31243, S323 = 31335, S324 = 31429, S325 = 31525, S326 = 31623, S327 = 31723,
+        S328 = 31825, S329 = 31916, S330 = 32022, S331 = 32117, S332 = 32214, S333 = 32313,
+        S334 = 32401, S335 = 32504, S336 = 32596, S337 = 32690, S338 = 32786, S339 = 32884,
+        S340 = 32984, S341 = 33086, S342 = 33177, S343 = 33283, S344 = 33378, S345 = 33475,
+        S346 = 33574, S347 = 33662, S348 = 33765, S349 = 33857, S350 = 33951, S351 = 34047,
+        S352 = 34145, S353 = 34245, S354 = 34347, S355 = 34438, S356 = 34544, S357 = 34639,
+        S358 = 34736, S359 = 34835, S360 = 34923, S361 = 35026, S362 = 35118, S363 = 35212,
+        S364 = 35308, S365 = 35406, S366 = 35506, S367 = 35608, S368 = 35699, S369 = 35805,
+        S370 = 35900, S371 = 35997, S372 = 36096, S373 = 36184, S374 = 36287, S375 = 36379,
+        S376 = 36473, S377 = 36569, S378 = 36667, S379 = 36767, S380 = 36869, S381 = 36960,
+        S382 = 37066, S383 = 37161, S384 = 37258, S385 = 37357, S386 = 37445, S387 = 37548,
+        S388 = 37640, S389 = 37734, S390 = 37830, S391 = 37928, S392 = 38028, S393 = 38130,
+        S394 = 38221, S395 = 38327, S396 = 38422, S397 = 38519, S398 = 38618, S399 = 38706,
+        S400 = 38809, S401 = 38901, S402 = 38995, S403 = 39091, S404 = 39189, S405 = 39289,
+        S406 = 39391, S407 = 39482, S408 = 39588, S409 = 39683, S410 = 39780, S411 = 39879,
+        S412 = 39967, S413 = 40070, S414 = 40162, S415 = 40256, S416 = 40352, S417 = 40450,
+        S418 = 40550, S419 = 40652, S420 = 40743, S421 = 40849, S422 = 40944, S423 = 41041,
+        S424 = 41140, S425 = 41228, S426 = 41331, S427 = 41423, S428 = 41517, S429 = 41613,
+        S430 = 41711, S431 = 41811, S432 = 41913, S433 = 42004, S434 = 42110, S435 = 42205,
+        S436 = 42302, S437 = 42401, S438 = 42489, S439 = 42592, S440 = 42684, S441 = 42778,
+        S442 = 42874, S443 = 42972, S444 = 43072, S445 = 43174, S446 = 43265, S447 = 43371,
+        S448 = 43466, S449 = 43563, S450 = 43662, S451 = 43750, S452 = 43853, S453 = 43945,
+        S454 = 44039, S455 = 44135, S456 = 44233, S457 = 44333, S458 = 44435, S459 = 44526,
+        S460 = 44632, S461 = 44727, S462 = 44824, S463 = 44923, S464 = 45011, S465 = 45114,
+        S466 = 45206, S467 = 45300, S468 = 45396, S469 = 45494, S470 = 45594, S471 = 45696,
+        S472 = 45787, S473 = 45893, S474 = 45988, S475 = 46085, S476 = 46184, S477 = 46272,
+        S478 = 46375, S479 = 46467, S480 = 46561, S481 = 46657, S482 = 46755, S483 = 46855,
+        S484 = 46957, S485 = 47048, S486 = 47154, S487 = 47249, S488 = 47346, S489 = 47445,
+        S490 = 47533, S491 = 47636, S492 = 47728, S493 = 47822, S494 = 47918, S495 = 48016,
+        S496 = 48116, S497 = 48218, S498 = 48309, S499 = 48415, S500 = 48510, S501 = 48607,
+        S502 = 48706, S503 = 48794, S504 = 48897, S505 = 48989, S506 = 49083, S507 = 49179,
+        S508 = 49277, S509 = 49377, S510 = 49479, S511 = 49570, S512 = 49676, S513 = 49771,
+        S514 = 49868, S515 = 49967, S516 = 50055, S517 = 50158, S518 = 50250, S519 = 50344,
+        S520 = 50440, S521 = 50538, S522 = 50638, S523 = 50740, S524 = 50831, S525 = 50937,
+        S526 = 51032, S527 = 51129, S528 = 51228, S529 = 51316, S530 = 51419, S531 = 51511,
+        S532 = 51605, S533 = 51701, S534 = 51799, S535 = 51899, S536 = 52001, S537 = 52092,
+        S538 = 52198, S539 = 52293, S540 = 52390, S541 = 52489, S542 = 52577, S543 = 52680,
+        S544 = 52772, S545 = 52866, S546 = 52962, S547 = 53060, S548 = 53160, S549 = 53262,
+        S550 = 53353, S551 = 53459, S552 = 53554, S553 = 53651, S554 = 53750, S555 = 53838,
+        S556 = 53941, S557 = 54033, S558 = 54127, S559 = 54223, S560 = 54321, S561 = 54421,
+        S562 = 54523, S563 = 54614, S564 = 54720, S565 = 54815, S566 = 54912, S567 = 55011,
+        S568 = 55099, S569 = 55202, S570 = 55294, S571 = 55388, S572 = 55484, S573 = 55582,
+        S574 = 55682, S575 = 55784, S576 = 55875, S577 = 55981, S578 = 56076, S579 = 56173,
+        S580 = 56272, S581 = 56360, S582 = 56463, S583 = 56555, S584 = 56649, S585 = 56745,
+        S586 = 56843, S587 = 56943, S588 = 57045, S589 = 57136, S590 = 57242, S591 = 57337,
+        S592 = 57434, S593 = 57533, S594 = 57621, S595 = 57724, S596 = 57816, S597 = 57910,
+        S598 = 58006, S599 = 58104,
+    }
+}
+
+/// Defines the same IntRange enum once for every `lookup`
+macro_rules! ranges {
+    ($($name: ident = $lookup: literal),*; $variants: tt) => {$(
+        #[allow(dead_code)]
+        #[derive(Debug, IntRange)]
+        #[repr(u16)]
+        #[inttype(lookup = $lookup)]
+        enum $name $variants
+    )*};
+}
+
+// 40 values out of every 50 from 0 to 2399
+ranges! {
+    RangeMatch = "match", RangeTable = "table", RangeSearch = "search"; {
+        #[range(0..40)] R0(u16), #[range(50..90)] R1(u16), #[range(100..140)] R2(u16),
+        #[range(150..190)] R3(u16), #[range(200..240)] R4(u16), #[range(250..290)] R5(u16),
+        #[range(300..340)] R6(u16), #[range(350..390)] R7(u16), #[range(400..440)] R8(u16),
+        #[range(450..490)] R9(u16), #[range(500..540)] R10(u16), #[range(550..590)] R11(u16),
+        #[range(600..640)] R12(u16), #[range(650..690)] R13(u16), #[range(700..740)] R14(u16),
+        #[range(750..790)] R15(u16), #[range(800..840)] R16(u16), #[range(850..890)] R17(u16),
+        #[range(900..940)] R18(u16), #[range(950..990)] R19(u16), #[range(1000..1040)] R20(u16),
+        #[range(1050..1090)] R21(u16), #[range(1100..1140)] R22(u16),
+        #[range(1150..1190)] R23(u16), #[range(1200..1240)] R24(u16),
+        #[range(1250..1290)] R25(u16), #[range(1300..1340)] R26(u16),
+        #[range(1350..1390)] R27(u16), #[range(1400..1440)] R28(u16),
+        #[range(1450..1490)] R29(u16), #[range(1500..1540)] R30(u16),
+        #[range(1550..1590)] R31(u16), #[range(1600..1640)] R32(u16),
+        #[range(1650..1690)] R33(u16), #[range(1700..1740)] R34(u16),
+        #[range(1750..1790)] R35(u16), #[range(1800..1840)] R36(u16),
+        #[range(1850..1890)] R37(u16), #[range(1900..1940)] R38(u16),
+        #[range(1950..1990)] R39(u16), #[range(2000..2040)] R40(u16),
+        #[range(2050..2090)] R41(u16), #[range(2100..2140)] R42(u16),
+        #[range(2150..2190)] R43(u16), #[range(2200..2240)] R44(u16),
+        #[range(2250..2290)] R45(u16), #[range(2300..2340)] R46(u16),
+        #[range(2350..2390)] R47(u16),
+    }
+}
+
+macro_rules! bench {
+    ($c: expr, $group: literal, $values: expr, $($name: ident),*) => {{
+        let values = $values;
+        let mut group = $c.benchmark_group($group);
+        $(
+            group.bench_function(stringify!($name), |b| {
+                b.iter(|| {
+                    for value in values.iter() {
+                        black_box($name::from_repr(black_box(*value)));
+                    }
+                })
+            });
+        )*
+        group.finish();
+    }};
+}
+
+/// `n` values evenly spread over `range`, shuffled so branches can't be predicted
+fn shuffled(range: core::ops::Range<u16>, n: usize) -> Vec<u16> {
+    let mut state = 0x2545_f491_u32;
+    let len = (range.end - range.start) as u32;
+    (0..n)
+        .map(|_| {
+            // xorshift32
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            range.start + (state % len) as u16
+        })
+        .collect()
+}
+
+fn lookup(c: &mut Criterion) {
+    bench!(
+        c,
+        "dense",
+        shuffled(900..1800, 1000),
+        DenseMatch,
+        DenseTable,
+        DenseSearch
+    );
+    bench!(
+        c,
+        "sparse",
+        shuffled(0..60000, 1000),
+        SparseMatch,
+        SparseSearch
+    );
+    bench!(
+        c,
+        "ranges",
+        shuffled(0..2400, 1000),
+        RangeMatch,
+        RangeTable,
+        RangeSearch
+    );
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
@@ -0,0 +1,455 @@
+use crate::util::{primitive_name, PRIMITIVES};
+use quote::format_ident;
+use syn::{
+    ext::IdentExt, punctuated::Punctuated, spanned::Spanned, Error, Ident, ItemEnum, Meta, Token,
+    Type, Variant,
+};
+
+/// Options given by `#[inttype(..)]` attributes on the enum
+#[derive(Default)]
+pub(crate) struct Options {
+    /// `#[inttype(type = u16)]`, the integer type to convert to and from
+    pub ty: Option<Type>,
+    /// `#[inttype(display)]`, generate `name()` and `Display`
+    pub display: bool,
+    /// `#[inttype(from_str)]`, generate `name()` and `FromStr`
+    pub from_str: bool,
+    /// `#[inttype(case_insensitive)]`, `FromStr` ignores the ASCII case of names
+    pub case_insensitive: bool,
+    /// `#[inttype(serde)]`, implement `Serialize` and `Deserialize`, requires the `serde` feature
+    pub serde: bool,
+    /// `#[inttype(serde_by_name)]`, human-readable serde formats use `Display` and `FromStr`
+    pub serde_by_name: bool,
+    /// `#[inttype(partial_eq)]`, compare the enum and the integer type with `==`
+    pub partial_eq: bool,
+    /// `#[inttype(partial_ord)]`, compare the enum and the integer type with `<` and `==`
+    pub partial_ord: bool,
+    /// `#[inttype(default)]`, implement `Default` as the `#[fallback]` variant
+    pub default: bool,
+    /// `#[inttype(int_conversions)]`, convert to the wider integer types and from all the others
+    pub int_conversions: bool,
+    /// `#[inttype(accessors)]`, generate `is_b()`, `new_b()`, `value()` and `range()` for an
+    /// IntRange enum
+    pub accessors: bool,
+    /// `#[inttype(kind)]` or `#[inttype(kind = Name)]`, the name of a field-less copy of an
+    /// IntRange enum, `{Enum}Kind` by default
+    pub kind: Option<Ident>,
+    /// `#[inttype(packed)]` or `#[inttype(packed = Name)]`, the name of a newtype holding only
+    /// the value of an IntRange enum, `{Enum}Packed` by default
+    pub packed: Option<Ident>,
+    /// `#[inttype(io)]`, generate `read_from` and `write_to`, requires the `std` feature
+    pub io: bool,
+    /// `#[inttype(lookup = "table" | "search" | "match")]`, how `from_repr` finds the variant,
+    /// chosen from the values when not given
+    pub lookup: Option<Lookup>,
+}
+
+/// How `from_repr` finds the variant of a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Lookup {
+    /// A `match` with an arm per value or range
+    Match,
+    /// An array indexed by the value
+    Table,
+    /// A binary search through the sorted ranges
+    Search,
+}
+
+impl Options {
+    pub fn parse(item: &ItemEnum) -> Result<Self, Error> {
+        let mut options = Self::default();
+
+        for attr in item.attrs.iter() {
+            if !attr.path().is_ident("inttype") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("type") {
+                    let ty: Type = meta.value()?.parse()?;
+                    if primitive_name(&ty).is_none() {
+                        return Err(Error::new(
+                            ty.span(),
+                            format!("`type` can be one of `{}`", PRIMITIVES.join(", ")),
+                        ));
+                    }
+                    options.ty = Some(ty);
+                    return Ok(());
+                }
+                if meta.path.is_ident("display") {
+                    options.display = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("from_str") {
+                    options.from_str = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("case_insensitive") {
+                    options.case_insensitive = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("serde") {
+                    if !cfg!(feature = "serde") {
+                        return Err(meta.error(
+                            "`#[inttype(serde)]` requires the `serde` feature of inttype-enum",
+                        ));
+                    }
+                    options.serde = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("serde_by_name") {
+                    options.serde_by_name = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("partial_eq") {
+                    options.partial_eq = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("partial_ord") {
+                    options.partial_ord = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("default") {
+                    options.default = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("int_conversions") {
+                    options.int_conversions = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("accessors") {
+                    options.accessors = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("kind") {
+                    options.kind = Some(match meta.input.peek(Token![=]) {
+                        true => meta.value()?.parse()?,
+                        false => format_ident!("{}Kind", item.ident),
+                    });
+                    return Ok(());
+                }
+                if meta.path.is_ident("packed") {
+                    options.packed = Some(match meta.input.peek(Token![=]) {
+                        true => meta.value()?.parse()?,
+                        false => format_ident!("{}Packed", item.ident),
+                    });
+                    return Ok(());
+                }
+                if meta.path.is_ident("io") {
+                    if !cfg!(feature = "std") {
+                        return Err(meta.error(
+                            "`#[inttype(io)]` requires the `std` feature of inttype-enum",
+                        ));
+                    }
+                    options.io = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("lookup") {
+                    let s: syn::LitStr = meta.value()?.parse()?;
+                    options.lookup = Some(match s.value().as_str() {
+                        "match" => Lookup::Match,
+                        "table" => Lookup::Table,
+                        "search" => Lookup::Search,
+                        _ => {
+                            return Err(Error::new(
+                                s.span(),
+                                "expected `\"table\"`, `\"search\"` or `\"match\"`",
+                            ))
+                        }
+                    });
+                    return Ok(());
+                }
+                Err(meta.error(
+                    "unsupported inttype attribute, expected one of `type = ..`, `display`, `from_str`, `case_insensitive`, `serde`, `serde_by_name`, `partial_eq`, `partial_ord`, `default`, `int_conversions`, `accessors`, `kind`, `packed`, `io`, `lookup = ..`",
+                ))
+            })?;
+        }
+
+        if options.packed.is_some() && options.kind.is_none() {
+            return Err(Error::new(
+                item.ident.span(),
+                "`#[inttype(packed)]` requires `#[inttype(kind)]`",
+            ));
+        }
+
+        if options.case_insensitive && !options.from_str {
+            return Err(Error::new(
+                item.ident.span(),
+                "`#[inttype(case_insensitive)]` requires `#[inttype(from_str)]`",
+            ));
+        }
+
+        if options.serde_by_name && !(options.serde && options.display && options.from_str) {
+            return Err(Error::new(
+                item.ident.span(),
+                "`#[inttype(serde_by_name)]` requires `#[inttype(serde, display, from_str)]`",
+            ));
+        }
+
+        Ok(options)
+    }
+}
+
+/// The name of a variant in strings, given by `#[name = ".."]` or else its identifier
+pub(crate) fn variant_name(variant: &Variant) -> Result<String, Error> {
+    let mut name = None;
+    for attr in variant.attrs.iter() {
+        if !attr.path().is_ident("name") {
+            continue;
+        }
+        if name.is_some() {
+            return Err(Error::new(attr.span(), "duplicated `#[name]` attribute"));
+        }
+        let value = &attr.meta.require_name_value()?.value;
+        match value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) if !s.value().is_empty() => name = Some(s.value()),
+            _ => {
+                return Err(Error::new(
+                    value.span(),
+                    "expected a non-empty string, like `#[name = \"connect\"]`",
+                ))
+            }
+        }
+    }
+    Ok(name.unwrap_or_else(|| variant.ident.unraw().to_string()))
+}
+
+/// The integer type to convert to and from: the one given by `#[inttype(type = ..)]`,
+/// otherwise the primitive representation of the enum.
+pub(crate) fn int_type(item: &ItemEnum, options: &Options) -> Result<Type, Error> {
+    if let Some(ty) = &options.ty {
+        // rustc rejects a primitive `repr` on them, but nothing stops `type = ..`
+        if item.variants.is_empty() {
+            return Err(Error::new(
+                item.ident.span(),
+                "an enum without variants has no value to convert, add a variant",
+            ));
+        }
+        return Ok(ty.clone());
+    }
+    match repr(item)? {
+        Some(ty) => Ok(ty),
+        None => Err(Error::new(item.span(), "no #[repr(inttype)] or #[inttype(type = inttype)] provided.\n`inttype` can be one of `u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, and isize`.\nSee https://doc.rust-lang.org/reference/type-layout.html#primitive-representations")),
+    }
+}
+
+/// Finds the primitive representation among the `#[repr(..)]` attributes of `item`,
+/// like `u8` in `#[repr(C, u8)]`.
+///
+/// `#[cfg_attr(.., repr(..))]` needs no special care, the compiler expands it before
+/// running derive macros.
+pub(crate) fn repr(item: &ItemEnum) -> Result<Option<Type>, Error> {
+    let mut found: Option<Ident> = None;
+
+    for attr in item.attrs.iter() {
+        if !attr.path().is_ident("repr") {
+            continue;
+        }
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            // `align(..)`, `packed(..)`, ...
+            let Meta::Path(path) = meta else {
+                continue;
+            };
+            let Some(ident) = path.get_ident() else {
+                continue;
+            };
+            if !PRIMITIVES.contains(&ident.to_string().as_str()) {
+                continue;
+            }
+            if let Some(prev) = &found {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("conflicting primitive representations `{prev}` and `{ident}`, only one is allowed"),
+                ));
+            }
+            found = Some(ident.clone());
+        }
+    }
+
+    Ok(found.map(|ident| syn::parse_quote!(#ident)))
+}
+
+/// Whether `item` has `#[repr(align(..))]`, which can make it larger than its primitive
+/// representation
+pub(crate) fn is_aligned(item: &ItemEnum) -> bool {
+    item.attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .any(|meta| meta.path().is_ident("align"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    fn repr_of(item: &str) -> Result<String, Error> {
+        let item = syn::parse_str::<ItemEnum>(item).unwrap();
+        let options = Options::parse(&item)?;
+        int_type(&item, &options).map(|ty| ty.to_token_stream().to_string())
+    }
+
+    #[test]
+    fn test_repr() {
+        assert_eq!(repr_of("#[repr(u8)] enum A { X }").unwrap(), "u8");
+        assert_eq!(repr_of("#[repr(C, u16)] enum A { X }").unwrap(), "u16");
+        assert_eq!(
+            repr_of("#[repr(i32, align(8))] enum A { X }").unwrap(),
+            "i32"
+        );
+        assert_eq!(
+            repr_of("#[repr(C)] #[repr(u64)] enum A { X }").unwrap(),
+            "u64"
+        );
+        assert_eq!(
+            repr_of("#[derive(Debug)] #[repr(usize)] enum A { X }").unwrap(),
+            "usize"
+        );
+
+        let aligned = |s: &str| is_aligned(&syn::parse_str::<ItemEnum>(s).unwrap());
+        assert!(aligned("#[repr(u8, align(4))] enum A { X }"));
+        assert!(aligned("#[repr(u8)] #[repr(align(2))] enum A { X }"));
+        assert!(!aligned("#[repr(C, u8)] enum A { X }"));
+
+        assert!(repr_of("enum A { X }").is_err());
+        assert!(repr_of("#[repr(C)] enum A { X }").is_err());
+        assert!(repr_of("#[repr(u8, u16)] enum A { X }").is_err());
+        assert!(repr_of("#[repr(u8)] #[repr(i8)] enum A { X }").is_err());
+    }
+
+    #[test]
+    fn test_type_option() {
+        assert_eq!(
+            repr_of("#[inttype(type = u16)] enum A { X }").unwrap(),
+            "u16"
+        );
+        assert_eq!(
+            repr_of("#[repr(u8)] #[inttype(type = u32)] enum A { X }").unwrap(),
+            "u32"
+        );
+        assert_eq!(
+            repr_of("#[inttype(type = i64)] #[repr(C)] enum A { X }").unwrap(),
+            "i64"
+        );
+
+        assert_eq!(
+            repr_of("#[inttype(type = u8)] enum A {}")
+                .unwrap_err()
+                .to_string(),
+            "an enum without variants has no value to convert, add a variant"
+        );
+        assert!(repr_of("#[inttype(type = String)] enum A { X }").is_err());
+        assert!(repr_of("#[inttype(typ = u8)] enum A { X }").is_err());
+        assert!(repr_of("#[inttype(type)] enum A { X }").is_err());
+    }
+
+    #[test]
+    fn test_string_options() {
+        let options = |s: &str| Options::parse(&syn::parse_str::<ItemEnum>(s).unwrap());
+        let o = options("#[inttype(display)] #[inttype(from_str, case_insensitive)] enum A { X }")
+            .unwrap();
+        assert!(o.display && o.from_str && o.case_insensitive);
+        let o = options("#[inttype(type = u8, from_str)] enum A { X }").unwrap();
+        assert!(!o.display && o.from_str && !o.case_insensitive);
+
+        let o = options("#[inttype(case_insensitive, from_str)] enum A { X }").unwrap();
+        assert!(o.from_str && o.case_insensitive);
+        let o = options("#[inttype(case_insensitive)] #[inttype(from_str)] enum A { X }").unwrap();
+        assert!(o.from_str && o.case_insensitive);
+
+        assert!(options("#[inttype(case_insensitive)] enum A { X }").is_err());
+        assert!(options("#[inttype(display = true)] enum A { X }").is_err());
+    }
+
+    #[test]
+    fn test_kind_options() {
+        let options = |s: &str| Options::parse(&syn::parse_str::<ItemEnum>(s).unwrap());
+        assert!(options("enum A { X }").unwrap().kind.is_none());
+        assert!(!options("enum A { X }").unwrap().accessors);
+        assert!(
+            options("#[inttype(accessors)] enum A { X }")
+                .unwrap()
+                .accessors
+        );
+        let o = options("#[inttype(kind)] enum A { X }").unwrap();
+        assert_eq!(o.kind.unwrap(), "AKind");
+        let o = options("#[inttype(kind = Category)] enum A { X }").unwrap();
+        assert_eq!(o.kind.unwrap(), "Category");
+
+        assert!(options("#[inttype(kind = \"Category\")] enum A { X }").is_err());
+
+        let o = options("#[inttype(kind, packed)] enum A { X }").unwrap();
+        assert_eq!(o.packed.unwrap(), "APacked");
+        let o = options("#[inttype(packed = Compact, kind)] enum A { X }").unwrap();
+        assert_eq!(o.packed.unwrap(), "Compact");
+
+        assert!(options("#[inttype(packed)] enum A { X }").is_err());
+    }
+
+    #[test]
+    fn test_lookup_option() {
+        let options = |s: &str| Options::parse(&syn::parse_str::<ItemEnum>(s).unwrap());
+        assert_eq!(options("enum A { X }").unwrap().lookup, None);
+        let o = options("#[inttype(lookup = \"table\")] enum A { X }").unwrap();
+        assert_eq!(o.lookup, Some(Lookup::Table));
+        let o = options("#[inttype(lookup = \"search\")] enum A { X }").unwrap();
+        assert_eq!(o.lookup, Some(Lookup::Search));
+        let o = options("#[inttype(lookup = \"match\")] enum A { X }").unwrap();
+        assert_eq!(o.lookup, Some(Lookup::Match));
+
+        assert!(options("#[inttype(lookup = \"hash\")] enum A { X }").is_err());
+        assert!(options("#[inttype(lookup = table)] enum A { X }").is_err());
+        assert!(options("#[inttype(lookup)] enum A { X }").is_err());
+    }
+
+    #[test]
+    fn test_feature_options() {
+        let options = |s: &str| Options::parse(&syn::parse_str::<ItemEnum>(s).unwrap());
+        assert!(!options("enum A { X }").unwrap().io);
+        match options("#[inttype(io)] enum A { X }") {
+            Ok(o) => assert!(cfg!(feature = "std") && o.io),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "`#[inttype(io)]` requires the `std` feature of inttype-enum"
+            ),
+        }
+
+        assert!(!options("enum A { X }").unwrap().serde);
+        match options("#[inttype(serde)] enum A { X }") {
+            Ok(o) => assert!(cfg!(feature = "serde") && o.serde),
+            Err(e) => assert_eq!(
+                e.to_string(),
+                "`#[inttype(serde)]` requires the `serde` feature of inttype-enum"
+            ),
+        }
+        if cfg!(feature = "serde") {
+            let o = options("#[inttype(serde, serde_by_name, display, from_str)] enum A { X }")
+                .unwrap();
+            assert!(o.serde_by_name);
+            assert!(options("#[inttype(serde, serde_by_name, display)] enum A { X }").is_err());
+        }
+        assert!(options("#[inttype(serde_by_name, display, from_str)] enum A { X }").is_err());
+    }
+
+    #[test]
+    fn test_variant_name() {
+        let name = |s: &str| variant_name(&syn::parse_str::<Variant>(s).unwrap());
+        assert_eq!(name("Connect").unwrap(), "Connect");
+        assert_eq!(name("r#Type").unwrap(), "Type");
+        assert_eq!(name("#[name = \"connect\"] Connect").unwrap(), "connect");
+        assert_eq!(name("#[name = \"b\"] B(u8)").unwrap(), "b");
+
+        assert!(name("#[name = \"\"] A").is_err());
+        assert!(name("#[name = b] A").is_err());
+        assert!(name("#[name(\"b\")] A").is_err());
+        assert!(name("#[name = \"a\"] #[name = \"b\"] A").is_err());
+    }
+}
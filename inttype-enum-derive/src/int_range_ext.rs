@@ -370,6 +370,14 @@ impl<T: Integer> RangeSubtracter<T> {
         self.vec.is_empty()
     }
 
+    pub fn ranges(&self) -> &[RangeInclusive<T>] {
+        &self.vec
+    }
+
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
     pub fn substract(&mut self, other: &impl RangeBounds<T>) -> Result<(), ()> {
         let mut ret = Err(());
 
@@ -404,6 +412,7 @@ impl<T: Integer> RangeSubtracter<T> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::reversed_empty_ranges)]
 mod tests {
     use super::*;
 
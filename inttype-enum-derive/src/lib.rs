@@ -0,0 +1,2097 @@
+//! Derive macros for [`inttype-enum`](https://docs.rs/inttype-enum/), use that crate instead,
+//! the generated code refers to it.
+
+mod attr;
+mod int_range_ext;
+mod lookup;
+mod util;
+use attr::Lookup;
+use lookup::Entry;
+use util::{is_literal_range, literal_value, primitive_name, RangeChecker};
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, spanned::Spanned, Error, ExprRange, Ident, ItemEnum,
+    Token, Type,
+};
+
+#[proc_macro_derive(IntType, attributes(inttype, default, fallback, other, name))]
+pub fn inttype(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemEnum);
+
+    let ident = &item.ident;
+    let options = match attr::Options::parse(&item) {
+        Ok(options) => options,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let ty = match attr::int_type(&item, &options) {
+        Ok(ty) => ty,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    if options.kind.is_some() || options.packed.is_some() {
+        return Error::new(
+            ident.span(),
+            "`#[inttype(kind)]` and `#[inttype(packed)]` are only supported by IntRange",
+        )
+        .into_compile_error()
+        .into();
+    }
+    let ty_str = ty.to_token_stream().to_string();
+    // `as` casts only give the right values if the enum is represented by `ty` itself
+    let is_repr = match attr::repr(&item) {
+        Ok(repr) => repr.is_some_and(|repr| primitive_name(&repr) == primitive_name(&ty)),
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    // `#[default]` chooses the fallback too, unless the `Default` variant differs from it
+    let fallback_attr = match item
+        .variants
+        .iter()
+        .any(|v| v.attrs.iter().any(|attr| attr.path().is_ident("fallback")))
+    {
+        true => "fallback",
+        false => "default",
+    };
+    let mut fallback_var = None;
+    let mut other_var = None;
+    let mut var = Vec::with_capacity(item.variants.len());
+    let mut discriminants = Vec::with_capacity(item.variants.len());
+    // the values of the unit variants, if every discriminant is a literal
+    let mut entries = Some(Vec::with_capacity(item.variants.len()));
+    // implicit discriminants count up from the last explicit one
+    let mut base = quote!(0);
+    let mut offset = 0usize;
+    let mut literal = Some(-1);
+    for (index, v) in item.variants.iter().enumerate() {
+        let discriminant = match &v.discriminant {
+            Some((_, n)) => {
+                base = n.to_token_stream();
+                offset = 0;
+                literal = literal_value(n);
+                quote!(#n)
+            }
+            None => {
+                let offset = proc_macro2::Literal::usize_unsuffixed(offset);
+                literal = literal.and_then(|n: i128| n.checked_add(1));
+                quote!((#base) + #offset)
+            }
+        };
+        offset += 1;
+
+        if v.attrs.iter().any(|attr| attr.path().is_ident("other")) {
+            if other_var.is_some() {
+                return Error::new(
+                    v.span(),
+                    "Multiple other variants supplied! should be only one!",
+                )
+                .into_compile_error()
+                .into();
+            }
+            let syn::Fields::Unnamed(fields) = &v.fields else {
+                return Error::new(
+                    v.span(),
+                    format!("other variant must have exactly one field, like `Unknown({ty_str})`"),
+                )
+                .into_compile_error()
+                .into();
+            };
+            if fields.unnamed.len() != 1
+                || primitive_name(&fields.unnamed[0].ty) != primitive_name(&ty)
+            {
+                return Error::new(
+                    fields.span(),
+                    format!("other variant must have exactly one field, like `Unknown({ty_str})`"),
+                )
+                .into_compile_error()
+                .into();
+            }
+            other_var = Some(&v.ident);
+            continue;
+        }
+
+        if v.attrs
+            .iter()
+            .any(|attr| attr.path().is_ident(fallback_attr))
+        {
+            if fallback_var.is_some() {
+                return Error::new(
+                    v.span(),
+                    format!("Multiple {fallback_attr} variants supplied! should be only one!"),
+                )
+                .into_compile_error()
+                .into();
+            }
+            fallback_var = Some(&v.ident);
+        }
+
+        if !matches!(v.fields, syn::Fields::Unit) {
+            return Error::new(
+                v.span(),
+                "every variant must be Unit kind, like `None`, except the `#[other]` one",
+            )
+            .into_compile_error()
+            .into();
+        }
+        var.push(&v.ident);
+        discriminants.push(discriminant);
+        entries = entries.zip(literal).map(|(mut entries, n)| {
+            entries.push(Entry {
+                start: n,
+                end: n,
+                variant: index,
+            });
+            entries
+        });
+    }
+
+    if let (Some(_), Some(other_var)) = (fallback_var, other_var) {
+        return Error::new(
+            other_var.span(),
+            format!("`#[{fallback_attr}]` and `#[other]` can't be used together"),
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    // `as` casts are not available once the enum carries data
+    let values = if is_repr && other_var.is_none() {
+        var.iter().map(|v| quote!(#ident::#v as #ty)).collect()
+    } else {
+        discriminants
+    };
+    let consts = match value_consts(&var) {
+        Ok(consts) => consts,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let docs = var
+        .iter()
+        .map(|v| format!("The value of [`{ident}::{v}`]."));
+
+    let to_repr = match other_var {
+        Some(other_var) => quote! {
+            match self {
+                #( Self::#var => Self::#consts, )*
+                Self::#other_var(n) => n,
+            }
+        },
+        None if is_repr => quote!(self as #ty),
+        None => quote! {
+            match self {
+                #( Self::#var => Self::#consts, )*
+            }
+        },
+    };
+    // what a value that no variant has converts into
+    let fallback = match (other_var, fallback_var) {
+        (Some(other_var), _) => Some(quote!(Self::#other_var(value))),
+        (None, Some(fallback_var)) => Some(quote!(Self::#fallback_var)),
+        (None, None) => None,
+    };
+    let unmatched = match &fallback {
+        Some(fallback) => quote!(core::option::Option::Some(#fallback)),
+        None => quote!(core::option::Option::None),
+    };
+    let lookup = match lookup::resolve(options.lookup, entries.as_deref(), ident.span()) {
+        Ok(lookup) => lookup,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    // represented by `ty` without fields, a run of discriminants converts by a transmute
+    let run = match (is_repr, other_var, options.lookup, &entries) {
+        (true, None, None, Some(entries)) if !attr::is_aligned(&item) => {
+            lookup::contiguous(entries)
+        }
+        _ => None,
+    };
+    // `From` goes through `from_repr` unless both are a `match`
+    let (from_repr, delegate) = match (run, lookup) {
+        (Some((start, end)), _) => (lookup::transmute(&item, &ty, start, end, &unmatched), true),
+        (None, Lookup::Match) => (
+            quote! {
+                #[allow(unreachable_patterns)]
+                match value {
+                    #( Self::#consts => core::option::Option::Some(Self::#var), )*
+                    _ => #unmatched,
+                }
+            },
+            false,
+        ),
+        (None, lookup) => (
+            lookup::from_repr(lookup, &item, &ty, entries.as_deref().unwrap(), &unmatched),
+            true,
+        ),
+    };
+
+    let count = item.variants.len();
+    let ranges = if fallback.is_some() {
+        quote! {
+            const RANGES: &[core::ops::RangeInclusive<#ty>] = &[<#ty>::MIN..=<#ty>::MAX];
+            RANGES
+        }
+    } else {
+        quote! {
+            const RANGES: &[core::ops::RangeInclusive<#ty>] = &[
+                #( #ident::#consts..=#ident::#consts, )*
+            ];
+            RANGES
+        }
+    };
+
+    let mut token_stream = quote! {
+        impl #ident {
+            #(
+                #[doc = #docs]
+                pub const #consts: #ty = #values;
+            )*
+
+            /// The number of variants.
+            pub const VARIANT_COUNT: usize = #count;
+
+            /// Every variant without a field, in declaration order.
+            pub const VARIANTS: &'static [Self] = &[#( Self::#var, )*];
+
+            /// Every value that converts into a variant.
+            pub fn values() -> impl core::iter::Iterator<Item = #ty> {
+                let ranges: &'static [core::ops::RangeInclusive<#ty>] = { #ranges };
+                ranges.iter().cloned().flatten()
+            }
+
+            /// Converts `value` into the matching variant, usable in `const` contexts.
+            pub const fn from_repr(value: #ty) -> core::option::Option<Self> {
+                #from_repr
+            }
+
+            /// Converts the variant into its value, usable in `const` contexts.
+            pub const fn to_repr(self) -> #ty {
+                #to_repr
+            }
+        }
+
+        impl From<#ident> for #ty {
+            fn from(value: #ident) -> Self {
+                value.to_repr()
+            }
+        }
+    };
+
+    // a payload that names a listed value would convert into that variant instead
+    let is_valid = match other_var {
+        Some(other_var) => quote! {
+            match self {
+                Self::#other_var(n) => matches!(Self::from_repr(*n), Some(Self::#other_var(_))),
+                _ => true,
+            }
+        },
+        None => quote!(true),
+    };
+    token_stream.extend(int_enum_impl(&item, &ty, is_valid));
+
+    let (error, error_impl) = try_from_error(&item, &ty, ranges);
+    token_stream.extend(error_impl);
+    token_stream.extend(int_conversions(&item, &ty, &options, &error));
+    token_stream.extend(bytes_impls(&item, &ty, &options, &error));
+    token_stream.extend(slice_impls(&item, &ty, &error));
+
+    let from = if let Some(fallback) = fallback {
+        let from = match delegate {
+            false => quote! {
+                #[allow(unreachable_patterns)]
+                match value {
+                    #( Self::#consts => Self::#var, )*
+                    _ => #fallback,
+                }
+            },
+            true => quote! {
+                match Self::from_repr(value) {
+                    core::option::Option::Some(v) => v,
+                    core::option::Option::None => #fallback,
+                }
+            },
+        };
+        quote! {
+            impl From<#ty> for #ident {
+                fn from(value: #ty) -> Self {
+                    #from
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl TryFrom<#ty> for #ident {
+                type Error = #error;
+
+                fn try_from(value: #ty) -> Result<Self, Self::Error> {
+                    // variants named `Ok` or `Err` may be glob imported next to the enum
+                    match Self::from_repr(value) {
+                        core::option::Option::Some(v) => core::result::Result::Ok(v),
+                        core::option::Option::None => core::result::Result::Err(#error { value, out_of_range: false }),
+                    }
+                }
+            }
+        }
+    };
+
+    token_stream.extend(from);
+    match default_impl(&item, &options, fallback_var) {
+        Ok(default) => token_stream.extend(default),
+        Err(e) => return e.into_compile_error().into(),
+    }
+    match string_impls(&item, &ty, &options) {
+        Ok(strings) => token_stream.extend(strings),
+        Err(e) => return e.into_compile_error().into(),
+    }
+    token_stream.extend(compare_impls(&item, &ty, &options));
+    token_stream.extend(serde_impls(&item, &ty, &options));
+    token_stream.into()
+}
+
+#[proc_macro_derive(IntRange, attributes(inttype, range, fallback, name))]
+pub fn new_inttype(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as ItemEnum);
+
+    let options = match attr::Options::parse(&item) {
+        Ok(options) => options,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let ty = match attr::int_type(&item, &options) {
+        Ok(ty) => ty,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let mut token_stream = match string_impls(&item, &ty, &options) {
+        Ok(strings) => strings,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    token_stream.extend(compare_impls(&item, &ty, &options));
+    token_stream.extend(serde_impls(&item, &ty, &options));
+    // `int_range` checks the `#[fallback]` variant
+    let fallback = item
+        .variants
+        .iter()
+        .find(|v| v.attrs.iter().any(|attr| attr.path().is_ident("fallback")))
+        .map(|v| &v.ident);
+    match default_impl(&item, &options, fallback) {
+        Ok(default) => token_stream.extend(default),
+        Err(e) => return e.into_compile_error().into(),
+    }
+
+    // `usize` and `isize` are as wide as the target's pointers, not the host's, so the
+    // ranges are worked out for every pointer width and the target picks its own
+    let widths: [(&str, Type); 3] = match primitive_name(&ty) {
+        Some("usize") => [
+            ("16", syn::parse_quote!(u16)),
+            ("32", syn::parse_quote!(u32)),
+            ("64", syn::parse_quote!(u64)),
+        ],
+        Some("isize") => [
+            ("16", syn::parse_quote!(i16)),
+            ("32", syn::parse_quote!(i32)),
+            ("64", syn::parse_quote!(i64)),
+        ],
+        _ => {
+            token_stream.extend(int_range(&item, &options, &ty, &ty));
+            return token_stream.into();
+        }
+    };
+
+    for (width, width_ty) in widths {
+        let file = syn::parse2::<syn::File>(int_range(&item, &options, &ty, &width_ty)).unwrap();
+        for item in file.items {
+            token_stream.extend(quote! {
+                #[cfg(target_pointer_width = #width)]
+                #item
+            });
+        }
+    }
+    token_stream.into()
+}
+
+/// Generates the `IntRange` implementation converting to and from `ty`, with the ranges
+/// evaluated as `checker_ty`, which is `ty` itself unless it is pointer sized.
+fn int_range(
+    item: &ItemEnum,
+    options: &attr::Options,
+    ty: &Type,
+    checker_ty: &Type,
+) -> proc_macro2::TokenStream {
+    let ident = &item.ident;
+    // patterns are left open at the limits of pointer sized types
+    let open = primitive_name(ty) != primitive_name(checker_ty);
+
+    let mut checker = match RangeChecker::new(checker_ty) {
+        Ok(checker) => checker,
+        Err(e) => return e.into_compile_error(),
+    };
+    // let mut variants = Vec::with_capacity(item.variants.len());
+    let mut unit_variants = Vec::with_capacity(item.variants.len());
+    let mut unit_discriminant = Vec::with_capacity(item.variants.len());
+    let mut unit_patterns = Vec::with_capacity(item.variants.len());
+    let mut unnamed_variants = Vec::with_capacity(item.variants.len());
+    let mut unnamed_ranges = Vec::with_capacity(item.variants.len());
+    // inclusive range expressions, in the order of `ranges()`
+    let mut ranges = Vec::with_capacity(item.variants.len());
+    // inclusive `(start, end)` bounds and owning variant of every range
+    let mut bounds = Vec::with_capacity(item.variants.len());
+    // indices into `bounds` of the ranges whose bounds are not literals
+    let mut const_ranges = Vec::new();
+    // `const` items holding the bounds of those ranges
+    let mut range_consts = Vec::new();
+    // (index into `ranges`, index into `unnamed_ranges`) of the `#[range(rest)]` variant
+    let mut rest = None;
+    // the `#[fallback]` variant every unclaimed value converts into
+    let mut fallback = None;
+    // index into `ranges` of the discriminant of the `#[fallback]` variant
+    let mut fallback_range = None;
+    // how many of `ranges` each variant owns, in declaration order
+    let mut range_counts = Vec::with_capacity(item.variants.len());
+
+    for v in item.variants.iter() {
+        let first_range = ranges.len();
+        if v.attrs.iter().any(|attr| attr.path().is_ident("fallback")) {
+            if !matches!(v.fields, syn::Fields::Unit) {
+                return Error::new(
+                    v.span(),
+                    "fallback variant must be Unit kind, `#[range(rest)]` keeps the value instead",
+                )
+                .into_compile_error();
+            }
+            if fallback.is_some() {
+                return Error::new(
+                    v.span(),
+                    "Multiple fallback variants supplied! should be only one!",
+                )
+                .into_compile_error();
+            }
+            fallback = Some(&v.ident);
+            fallback_range = Some(first_range);
+        }
+        match &v.fields {
+            syn::Fields::Named(_) => {
+                return Error::new(
+                    v.fields.span(),
+                    "variant can only be Unit/Unamed kind, Examples: A=0,B(u8),",
+                )
+                .into_compile_error()
+            }
+            //#[repr(u8)] #[derive(IntType)] enum { #[range(1..5)]a(u8), }
+            syn::Fields::Unnamed(fields) => {
+                if fields.unnamed.len() != 1 {
+                    return Error::new(
+                        fields.span(),
+                        format!(
+                            "Unnamed variant can only have 1 field, here it must be {}",
+                            ty.into_token_stream()
+                        ),
+                    )
+                    .into_compile_error();
+                }
+                if let Some((_, discriminant)) = &v.discriminant {
+                    return Error::new(
+                        discriminant.span(),
+                        "Unnamed variant can't have discriminant",
+                    )
+                    .into_compile_error();
+                }
+                for unamed in fields.unnamed.iter() {
+                    if primitive_name(&unamed.ty) != primitive_name(ty) {
+                        return Error::new(
+                            fields.span(),
+                            format!(
+                                "Unnamed variant's field must be the same type as its repr: {}",
+                                ty.into_token_stream()
+                            ),
+                        )
+                        .into_compile_error();
+                    }
+                }
+
+                // patterns of every range this variant owns
+                let mut patterns = Vec::new();
+                let mut is_rest = false;
+
+                for attr in v.attrs.iter() {
+                    if !attr.path().is_ident("range") {
+                        continue;
+                    }
+                    let exprs = match attr
+                        .parse_args_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)
+                    {
+                        Ok(exprs) => exprs,
+                        Err(e) => return e.into_compile_error(),
+                    };
+                    for expr in exprs {
+                        if is_rest {
+                            return Error::new(
+                                expr.span(),
+                                "`#[range(rest)]` can't be combined with other ranges",
+                            )
+                            .into_compile_error();
+                        }
+                        let range = match expr {
+                            syn::Expr::Path(path) if path.path.is_ident("rest") => {
+                                if !patterns.is_empty() {
+                                    return Error::new(
+                                        path.span(),
+                                        "`#[range(rest)]` can't be combined with other ranges",
+                                    )
+                                    .into_compile_error();
+                                }
+                                if rest.is_some() {
+                                    return Error::new(
+                                        path.span(),
+                                        "Only one variant can have `#[range(rest)]`",
+                                    )
+                                    .into_compile_error();
+                                }
+                                // filled in once every other variant has claimed its range
+                                rest = Some((ranges.len(), unnamed_ranges.len()));
+                                is_rest = true;
+                                continue;
+                            }
+                            syn::Expr::Range(range) => range,
+                            expr => {
+                                return Error::new(
+                                    expr.span(),
+                                    "expected a range like `1..5`, or `rest`",
+                                )
+                                .into_compile_error()
+                            }
+                        };
+
+                        // println!("cur ident: {}", v.ident.to_string());
+
+                        // println!("range: {}", range.to_token_stream());
+                        let (start, end) = if is_literal_range(&range) {
+                            if let Err(e) = checker.substract(&range) {
+                                return e.into_compile_error();
+                            }
+                            let inclusive_expr = checker.expr_to_inclusive_expr(&range).unwrap();
+                            let start = inclusive_expr.start.to_token_stream();
+                            let end = inclusive_expr.end.to_token_stream();
+                            ranges.push(inclusive_expr.into_token_stream());
+                            patterns.push(checker.expr_to_pattern(&range, open).unwrap());
+                            (start, end)
+                        } else {
+                            let (start, end) =
+                                const_range(&range, ty, bounds.len(), &mut range_consts);
+                            const_ranges.push(bounds.len());
+                            ranges.push(quote!(#start..=#end));
+                            patterns.push(quote!(#start..=#end));
+                            (start, end)
+                        };
+
+                        bounds.push((start, end, &v.ident));
+                    }
+                }
+                if !is_rest && patterns.is_empty() {
+                    return Error::new(
+                        fields.span(),
+                        "at least one range must be provided for Unnamed variant, like `#[range(1..5)]`",
+                    )
+                    .into_compile_error();
+                }
+                unnamed_variants.push(&v.ident);
+                unnamed_ranges.push(quote!(#(#patterns)|*));
+            }
+            //#[repr(u8)] #[derive(IntType)] enum { a=0, }
+            syn::Fields::Unit => {
+                if v.attrs.iter().any(|attr| attr.path().is_ident("range")) {
+                    return Error::new(v.span(), "Unit variant should not have `range` attribute")
+                        .into_compile_error();
+                }
+                // let s = v.ident.to_string();
+                // println!("v: {}", v.to_token_stream());
+                // println!("cur unit ident: {}", v.ident.to_string());
+                match v.discriminant.as_ref() {
+                    Some((_, n)) => {
+                        let s = n.to_token_stream().to_string();
+                        let range =
+                            syn::parse_str::<ExprRange>(format!("({})..=({})", s, s).as_str())
+                                .unwrap();
+                        let (pattern, value) = if is_literal_range(&range) {
+                            if let Err(e) = checker.substract(&range) {
+                                // println!("e.span(): {:?}", e.span());
+                                return Error::new(n.span(), e.to_string()).into_compile_error();
+                            }
+                            ranges.push(
+                                checker
+                                    .expr_to_inclusive_expr(&range)
+                                    .unwrap()
+                                    .into_token_stream(),
+                            );
+                            let pattern = if open {
+                                checker.expr_to_pattern(&range, open).unwrap()
+                            } else {
+                                n.to_token_stream()
+                            };
+                            (pattern, n.to_token_stream())
+                        } else {
+                            let (value, _) =
+                                const_range(&range, ty, bounds.len(), &mut range_consts);
+                            const_ranges.push(bounds.len());
+                            ranges.push(quote!(#value..=#value));
+                            (value.clone(), value)
+                        };
+                        bounds.push((value.clone(), value, &v.ident));
+                        unit_patterns.push(pattern);
+                        unit_discriminant.push(n);
+                        unit_variants.push(&v.ident);
+                    }
+                    None => {
+                        return Error::new(v.span(), "must specify discriminant, like A=0")
+                            .into_compile_error()
+                    }
+                }
+            }
+        }
+        // println!("ident: {}", v.ident.to_string());
+        range_counts.push(ranges.len() - first_range);
+    }
+
+    // println!("checker.is_empty(): {}", checker.is_empty());
+    // println!("ranges: {:?}", ranges.iter().map(|r| r.to_token_stream()).collect::<Vec<_>>());
+    // println!("ty: {}", ty.to_token_stream());
+    // println!("ident: {}", ident);
+    // println!("unit_variants: {:?}", unit_variants);
+    // println!("unit_discriminant: {:?}", unit_discriminant.iter().map(|r| r.to_token_stream()).collect::<Vec<_>>());
+    // println!("unnamed_variants: {:?}", unnamed_variants);
+    // println!("unnamed_ranges: {:?}", unnamed_ranges.iter().map(|r| r.to_token_stream()).collect::<Vec<_>>());
+
+    let mut all_ranges = ranges;
+
+    if let (Some(fallback), Some(_)) = (fallback, rest) {
+        return Error::new(
+            fallback.span(),
+            "`#[fallback]` and `#[range(rest)]` can't be used together",
+        )
+        .into_compile_error();
+    }
+
+    if let Some((range_index, unnamed_index)) = rest {
+        if !const_ranges.is_empty() {
+            return Error::new(
+                unnamed_variants[unnamed_index].span(),
+                "`#[range(rest)]` requires every range and discriminant to be an integer literal",
+            )
+            .into_compile_error();
+        }
+        let leftover = checker.remaining();
+        if leftover.is_empty() {
+            return Error::new(
+                unnamed_variants[unnamed_index].span(),
+                "no values left for the `#[range(rest)]` variant",
+            )
+            .into_compile_error();
+        }
+        let patterns = leftover
+            .iter()
+            .map(|r| checker.expr_to_pattern(r, open).unwrap());
+        unnamed_ranges[unnamed_index] = quote!(#(#patterns)|*);
+        all_ranges.splice(
+            range_index..range_index,
+            leftover.iter().map(|r| r.to_token_stream()),
+        );
+        let rest_ident = unnamed_variants[unnamed_index];
+        let rest_index = item.variants.iter().position(|v| v.ident == *rest_ident);
+        range_counts[rest_index.unwrap()] = leftover.len();
+        for r in leftover.iter() {
+            let start = r.start.to_token_stream();
+            let end = r.end.to_token_stream();
+            bounds.push((start, end, rest_ident));
+        }
+        checker.clear();
+    }
+
+    // the `#[fallback]` variant owns its discriminant and every value no range claims,
+    // listed after the ranges of the other variants
+    let mut gaps = None;
+    if let Some(fallback_range) = fallback_range {
+        let discriminant = all_ranges.remove(fallback_range);
+        all_ranges.push(discriminant);
+        if const_ranges.is_empty() {
+            all_ranges.extend(checker.remaining().iter().map(|r| r.to_token_stream()));
+        } else {
+            gaps = Some(fallback_gaps(ty, &bounds));
+        }
+    }
+    // the part of `ranges()` each variant owns, in declaration order
+    let mut start = 0;
+    let mut range_slices = Vec::with_capacity(item.variants.len());
+    for (v, count) in item.variants.iter().zip(&range_counts) {
+        if Some(&v.ident) == fallback {
+            range_slices.push(quote!());
+            continue;
+        }
+        let end = start + count;
+        range_slices.push(quote!(#start..#end));
+        start = end;
+    }
+    if let Some(i) = item
+        .variants
+        .iter()
+        .position(|v| Some(&v.ident) == fallback)
+    {
+        range_slices[i] = quote!(#start..);
+    }
+    let ranges = match gaps {
+        None => quote! {
+            const RANGES: &[core::ops::RangeInclusive<#ty>] = &[#(#all_ranges,)*];
+            RANGES
+        },
+        Some((gaps, gap_ranges)) => {
+            let count = all_ranges.len();
+            quote! {
+                #gaps
+                const ALL: &[core::ops::RangeInclusive<#ty>] = &[#(#all_ranges,)* #(#gap_ranges,)*];
+                const RANGES: &[core::ops::RangeInclusive<#ty>] = ALL.split_at(#count + GAPS.1).0;
+                RANGES
+            }
+        }
+    };
+
+    // the values of every variant, if they are all literals
+    let entries = const_ranges.is_empty().then(|| {
+        bounds
+            .iter()
+            .map(|(start, end, var)| {
+                let value =
+                    |n| literal_value(&syn::parse2(proc_macro2::TokenStream::clone(n)).ok()?);
+                Some(Entry {
+                    start: value(start)?,
+                    end: value(end)?,
+                    variant: item.variants.iter().position(|v| v.ident == **var)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+    });
+    let entries = entries.flatten();
+
+    let consts = match value_consts(&unit_variants) {
+        Ok(consts) => consts,
+        Err(e) => return e.into_compile_error(),
+    };
+    let docs = unit_variants
+        .iter()
+        .map(|v| format!("The value of [`{ident}::{v}`]."));
+
+    let count = item.variants.len();
+    let unmatched = match fallback {
+        Some(fallback) => quote!(core::option::Option::Some(Self::#fallback)),
+        None => quote!(core::option::Option::None),
+    };
+    let lookup = match lookup::resolve(options.lookup, entries.as_deref(), ident.span()) {
+        Ok(lookup) => lookup,
+        Err(e) => return e.into_compile_error(),
+    };
+    let from_repr = match lookup {
+        Lookup::Match => quote! {
+            #[allow(unreachable_patterns)]
+            #[allow(non_contiguous_range_endpoints)]
+            match value {
+                #(
+                    #unit_patterns => core::option::Option::Some(Self::#unit_variants),
+                )*
+                #(
+                    #unnamed_ranges => core::option::Option::Some(Self::#unnamed_variants(value)),
+                )*
+                _ => #unmatched,
+            }
+        },
+        lookup => lookup::from_repr(lookup, item, ty, entries.as_deref().unwrap(), &unmatched),
+    };
+
+    let mut token_stream = quote! {
+        impl #ident {
+            #(
+                #[doc = #docs]
+                pub const #consts: #ty = #unit_discriminant;
+            )*
+
+            /// The number of variants.
+            pub const VARIANT_COUNT: usize = #count;
+
+            /// Every value that converts into a variant, walking [`Self::ranges`].
+            pub fn values() -> impl core::iter::Iterator<Item = #ty> {
+                Self::ranges().iter().cloned().flatten()
+            }
+
+            /// Converts `value` into the variant whose range holds it, usable in `const` contexts.
+            pub const fn from_repr(value: #ty) -> core::option::Option<Self> {
+                #(#range_consts)*
+                #from_repr
+            }
+
+            /// Converts the variant into its value, usable in `const` contexts.
+            pub const fn to_repr(self) -> #ty {
+                match self {
+                    #(
+                        Self::#unit_variants => Self::#consts,
+                    )*
+                    #(
+                        Self::#unnamed_variants(n) => n,
+                    )*
+                }
+            }
+        }
+
+        impl From<#ident> for #ty {
+            fn from(value: #ident) -> Self {
+                value.to_repr()
+            }
+        }
+
+        impl #ident {
+            pub fn ranges() -> &'static [core::ops::RangeInclusive<#ty>] {
+                #(#range_consts)*
+                #ranges
+            }
+            pub fn is_valid(&self) -> bool {
+                #(#range_consts)*
+                match self {
+                    #(
+                        Self::#unit_variants => true,
+                    )*
+                    #(
+                        #[allow(unreachable_patterns)]
+                        #[allow(non_contiguous_range_endpoints)]
+                        Self::#unnamed_variants(n) => match n {
+                            #unnamed_ranges => true,
+                            _ => false,
+                        },
+                    )*
+                }
+            }
+        }
+    };
+
+    if !const_ranges.is_empty() {
+        token_stream.extend(const_range_checks(
+            ident,
+            &bounds,
+            &const_ranges,
+            &range_consts,
+        ));
+    }
+
+    let (error, error_impl) = try_from_error(item, ty, quote!(#ident::ranges()));
+    token_stream.extend(error_impl);
+    token_stream.extend(int_conversions(item, ty, options, &error));
+    token_stream.extend(bytes_impls(item, ty, options, &error));
+    token_stream.extend(slice_impls(item, ty, &error));
+
+    // the coverage of non-literal ranges is unknown until compile time
+    let ty_to_ident = if lookup != Lookup::Match && (fallback.is_some() || checker.is_empty()) {
+        let unmatched = match fallback {
+            Some(fallback) => quote!(Self::#fallback),
+            None => quote!(core::unreachable!()),
+        };
+        quote! {
+            impl From<#ty> for #ident {
+                fn from(value: #ty) -> Self {
+                    match Self::from_repr(value) {
+                        core::option::Option::Some(v) => v,
+                        core::option::Option::None => #unmatched,
+                    }
+                }
+            }
+        }
+    } else if let Some(fallback) = fallback {
+        quote! {
+            impl From<#ty> for #ident {
+                fn from(value: #ty) -> Self {
+                    #(#range_consts)*
+                    #[allow(unreachable_patterns)]
+                    #[allow(non_contiguous_range_endpoints)]
+                    match value {
+                        #(
+                            #unit_patterns => Self::#unit_variants,
+                        )*
+                        #(
+                            #unnamed_ranges => Self::#unnamed_variants(value),
+                        )*
+                        _ => Self::#fallback,
+                    }
+                }
+            }
+        }
+    } else if checker.is_empty() && const_ranges.is_empty() {
+        quote! {
+            impl From<#ty> for #ident {
+                fn from(value: #ty) -> Self {
+                    match value {
+                        #(
+                            #unit_patterns => Self::#unit_variants,
+                        )*
+                        #(
+                            #unnamed_ranges => Self::#unnamed_variants(value),
+                        )*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl TryFrom<#ty> for #ident {
+                type Error = #error;
+
+                fn try_from(value: #ty) -> Result<Self, Self::Error> {
+                    match Self::from_repr(value) {
+                        core::option::Option::Some(v) => core::result::Result::Ok(v),
+                        core::option::Option::None => core::result::Result::Err(#error { value, out_of_range: false }),
+                    }
+                }
+            }
+        }
+    };
+
+    token_stream.extend(ty_to_ident);
+
+    token_stream.extend(int_enum_impl(item, ty, quote!(#ident::is_valid(self))));
+    if options.accessors {
+        // the other impls are still emitted, so the error is the only one reported
+        match variant_accessors(
+            item,
+            ty,
+            &range_slices,
+            &unnamed_variants,
+            &unnamed_ranges,
+            &range_consts,
+        ) {
+            Ok(accessors) => token_stream.extend(accessors),
+            Err(e) => token_stream.extend(e.into_compile_error()),
+        }
+    }
+    if let Some(kind) = &options.kind {
+        token_stream.extend(kind_impls(item, ty, kind, &range_slices));
+        if let Some(packed) = &options.packed {
+            token_stream.extend(packed_impls(item, ty, kind, packed));
+        }
+    }
+    token_stream.extend(quote! {
+        impl ::inttype_enum::IntRangeEnum for #ident {
+            fn ranges() -> &'static [core::ops::RangeInclusive<#ty>] {
+                #ident::ranges()
+            }
+        }
+    });
+    token_stream
+}
+
+/// The associated const holding the value of the unit variant `var`, like `NOT_FOUND_VALUE`
+fn value_const(var: &Ident) -> Ident {
+    let name = util::to_snake_case(&var.to_string()).to_uppercase();
+    format_ident!("{}_VALUE", name, span = var.span())
+}
+
+/// `value_const` of every variant in `vars`, rejecting names that give the same constant
+fn value_consts(vars: &[&Ident]) -> Result<Vec<Ident>, Error> {
+    let mut consts: Vec<Ident> = Vec::with_capacity(vars.len());
+    for var in vars {
+        let value = value_const(var);
+        if let Some(prev) = consts.iter().position(|c| *c == value) {
+            return Err(Error::new(
+                var.span(),
+                format!(
+                    "`{var}` and `{}` both have their value in `{value}`, rename one of them",
+                    vars[prev]
+                ),
+            ));
+        }
+        consts.push(value);
+    }
+    Ok(consts)
+}
+
+/// The `is_b()` of every variant in `vars`, rejecting names that give the same predicate
+/// or `is_valid`
+fn is_fns(vars: &[&Ident]) -> Result<Vec<Ident>, Error> {
+    let mut fns: Vec<Ident> = Vec::with_capacity(vars.len());
+    for var in vars {
+        let is_fn = format_ident!(
+            "is_{}",
+            util::to_snake_case(&var.to_string()),
+            span = var.span()
+        );
+        if is_fn == "is_valid" {
+            return Err(Error::new(
+                var.span(),
+                format!("`{var}` would get `is_valid`, which already checks the value of every variant, rename it"),
+            ));
+        }
+        if let Some(prev) = fns.iter().position(|f| *f == is_fn) {
+            return Err(Error::new(
+                var.span(),
+                format!(
+                    "`{var}` and `{}` would both get `{is_fn}`, rename one of them",
+                    vars[prev]
+                ),
+            ));
+        }
+        fns.push(is_fn);
+    }
+    Ok(fns)
+}
+
+/// `is_b()` for every variant, `new_b()` for every variant with a field, and `value()` and
+/// `range()` for the enum, `range_slices` says which of `ranges()` each variant owns
+fn variant_accessors(
+    item: &ItemEnum,
+    ty: &Type,
+    range_slices: &[proc_macro2::TokenStream],
+    unnamed_variants: &[&Ident],
+    unnamed_ranges: &[proc_macro2::TokenStream],
+    range_consts: &[proc_macro2::TokenStream],
+) -> Result<proc_macro2::TokenStream, Error> {
+    let ident = &item.ident;
+    let snake = |v: &Ident| util::to_snake_case(&v.to_string());
+
+    let is_fns = is_fns(&item.variants.iter().map(|v| &v.ident).collect::<Vec<_>>())?;
+    let is_patterns = item.variants.iter().map(|v| {
+        let var = &v.ident;
+        match v.fields {
+            syn::Fields::Unit => quote!(Self::#var),
+            _ => quote!(Self::#var(_)),
+        }
+    });
+    let is_docs = item
+        .variants
+        .iter()
+        .map(|v| format!("Whether `self` is [`{ident}::{}`].", v.ident));
+
+    let new_fns = unnamed_variants
+        .iter()
+        .map(|v| format_ident!("new_{}", snake(v), span = v.span()));
+    let new_docs = unnamed_variants.iter().map(|v| {
+        format!("[`{ident}::{v}`] holding `value`, or `None` if `value` is outside its ranges.")
+    });
+
+    let range_arms = item.variants.iter().zip(range_slices).map(|(v, slice)| {
+        let var = &v.ident;
+        let ranges = quote!((&#ident::ranges()[#slice]));
+        match v.fields {
+            syn::Fields::Unit => quote!(Self::#var => #ranges[0].clone()),
+            _ => quote! {
+                Self::#var(n) => {
+                    let ranges: &[core::ops::RangeInclusive<#ty>] = #ranges;
+                    ranges.iter().find(|r| r.contains(n)).unwrap_or(&ranges[0]).clone()
+                }
+            },
+        }
+    });
+    let value = value_of_ref(item, quote!(self));
+    let range_consts = quote!(#(#range_consts)*);
+
+    Ok(quote! {
+        impl #ident {
+            #(
+                #[doc = #is_docs]
+                pub const fn #is_fns(&self) -> bool {
+                    matches!(self, #is_patterns)
+                }
+            )*
+
+            #(
+                #[doc = #new_docs]
+                pub const fn #new_fns(value: #ty) -> core::option::Option<Self> {
+                    #range_consts
+                    #[allow(unreachable_patterns)]
+                    #[allow(non_contiguous_range_endpoints)]
+                    match value {
+                        #unnamed_ranges => core::option::Option::Some(Self::#unnamed_variants(value)),
+                        _ => core::option::Option::None,
+                    }
+                }
+            )*
+
+            /// The value of the variant, its field or its discriminant.
+            pub const fn value(&self) -> #ty {
+                #value
+            }
+
+            /// The range of the variant that holds its value, or the first one of the variant
+            /// if none does.
+            pub fn range(&self) -> core::ops::RangeInclusive<#ty> {
+                match self {
+                    #( #range_arms, )*
+                }
+            }
+        }
+    })
+}
+
+/// The field-less enum `kind` with a variant for every variant of the IntRange enum,
+/// `range_slices` says which of `ranges()` each variant owns
+fn kind_impls(
+    item: &ItemEnum,
+    ty: &Type,
+    kind: &Ident,
+    range_slices: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let vis = &item.vis;
+    let ident = &item.ident;
+    let vars = item.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let docs = item.variants.iter().map(|v| {
+        v.attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .collect::<Vec<_>>()
+    });
+    let patterns = item.variants.iter().map(|v| {
+        let var = &v.ident;
+        match v.fields {
+            syn::Fields::Unit => quote!(#ident::#var),
+            _ => quote!(#ident::#var(_)),
+        }
+    });
+    let slices = range_slices
+        .iter()
+        .map(|slice| quote!(&#ident::ranges()[#slice]));
+    let doc = format!("Which variant of [`{ident}`] a value is, without its field.");
+    let ranges_doc = format!("The ranges of this kind, in the order of [`{ident}::ranges`].");
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #vis enum #kind {
+            #(
+                #(#docs)*
+                #vars,
+            )*
+        }
+
+        impl #kind {
+            /// Every kind, in declaration order.
+            pub const VARIANTS: &'static [Self] = &[#( Self::#vars, )*];
+
+            #[doc = #ranges_doc]
+            pub fn ranges(self) -> &'static [core::ops::RangeInclusive<#ty>] {
+                match self {
+                    #( Self::#vars => #slices, )*
+                }
+            }
+
+            /// The first of [`Self::ranges`], the only one unless the variant has several.
+            pub fn range(self) -> core::ops::RangeInclusive<#ty> {
+                self.ranges()[0].clone()
+            }
+
+            /// Whether `value` converts into a variant of this kind.
+            pub fn contains(self, value: #ty) -> bool {
+                #ident::from_repr(value).is_some_and(|v| v.kind() == self)
+            }
+        }
+
+        impl #ident {
+            /// Which variant `self` is, without its field.
+            pub const fn kind(&self) -> #kind {
+                match self {
+                    #( #patterns => #kind::#vars, )*
+                }
+            }
+        }
+
+        impl From<&#ident> for #kind {
+            fn from(value: &#ident) -> Self {
+                value.kind()
+            }
+        }
+    }
+}
+
+/// The newtype `packed` holding just the value of a variant of the IntRange enum,
+/// always one that converts back into the same variant
+fn packed_impls(
+    item: &ItemEnum,
+    ty: &Type,
+    kind: &Ident,
+    packed: &Ident,
+) -> proc_macro2::TokenStream {
+    let vis = &item.vis;
+    let ident = &item.ident;
+    let doc = format!(
+        "[`{ident}`] stored as just its value, as small as `{}`.",
+        ty.to_token_stream()
+    );
+    let new_doc = format!(
+        "Packs the variant `value` converts into, `None` if it doesn't convert into [`{ident}`]."
+    );
+    let unpack_doc = format!("The packed value as [`{ident}`].");
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        #vis struct #packed(#ty);
+
+        impl #packed {
+            #[doc = #new_doc]
+            pub const fn new(value: #ty) -> core::option::Option<Self> {
+                match #ident::from_repr(value) {
+                    core::option::Option::Some(v) => core::option::Option::Some(Self(v.to_repr())),
+                    core::option::Option::None => core::option::Option::None,
+                }
+            }
+
+            /// The packed value.
+            pub const fn value(self) -> #ty {
+                self.0
+            }
+
+            /// Which variant the packed value is.
+            pub const fn kind(self) -> #kind {
+                self.unpack().kind()
+            }
+
+            #[doc = #unpack_doc]
+            pub const fn unpack(self) -> #ident {
+                match #ident::from_repr(self.0) {
+                    core::option::Option::Some(v) => v,
+                    // only values that convert are ever packed
+                    core::option::Option::None => core::unreachable!(),
+                }
+            }
+        }
+
+        impl From<#packed> for #ident {
+            fn from(value: #packed) -> Self {
+                value.unpack()
+            }
+        }
+
+        /// Gives `value` back if its field is outside the ranges of its variant.
+        impl TryFrom<#ident> for #packed {
+            type Error = #ident;
+
+            fn try_from(value: #ident) -> core::result::Result<Self, Self::Error> {
+                match value.is_valid() {
+                    true => core::result::Result::Ok(Self(value.to_repr())),
+                    false => core::result::Result::Err(value),
+                }
+            }
+        }
+
+        impl From<#packed> for #ty {
+            fn from(value: #packed) -> Self {
+                value.0
+            }
+        }
+    }
+}
+
+/// `impl Default` returning the fallback variant, for `#[inttype(default)]`
+fn default_impl(
+    item: &ItemEnum,
+    options: &attr::Options,
+    fallback: Option<&Ident>,
+) -> Result<proc_macro2::TokenStream, Error> {
+    if !options.default {
+        return Ok(quote!());
+    }
+    let ident = &item.ident;
+    let Some(fallback) = fallback else {
+        return Err(Error::new(
+            ident.span(),
+            "`#[inttype(default)]` requires a `#[fallback]` variant",
+        ));
+    };
+    Ok(quote! {
+        impl core::default::Default for #ident {
+            fn default() -> Self {
+                Self::#fallback
+            }
+        }
+    })
+}
+
+/// `impl IntEnum` on top of the inherent `from_repr` and `to_repr`,
+/// `is_valid` is an expression body with `self: &Self` in scope
+fn int_enum_impl(
+    item: &ItemEnum,
+    ty: &Type,
+    is_valid: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let ident = &item.ident;
+    let count = item.variants.len();
+    quote! {
+        impl ::inttype_enum::IntEnum for #ident {
+            type Repr = #ty;
+
+            const VARIANT_COUNT: usize = #count;
+
+            fn from_repr(value: #ty) -> core::option::Option<Self> {
+                #ident::from_repr(value)
+            }
+
+            fn to_repr(self) -> #ty {
+                #ident::to_repr(self)
+            }
+
+            fn is_valid(&self) -> bool {
+                #is_valid
+            }
+
+            fn values() -> impl core::iter::Iterator<Item = #ty> {
+                #ident::values()
+            }
+        }
+    }
+}
+
+/// `name()` plus `Display` and/or `FromStr`, as asked by `options`.
+/// Every variant is either a unit or has a single field of `ty`.
+fn string_impls(
+    item: &ItemEnum,
+    ty: &Type,
+    options: &attr::Options,
+) -> Result<proc_macro2::TokenStream, Error> {
+    if !options.display && !options.from_str {
+        return Ok(quote!());
+    }
+    let ident = &item.ident;
+    let type_name = ident.to_string();
+    let ignore_case = options.case_insensitive;
+
+    let mut names = Vec::with_capacity(item.variants.len());
+    let mut patterns = Vec::with_capacity(item.variants.len());
+    let mut units = (Vec::new(), Vec::new());
+    let mut tuples = (Vec::new(), Vec::new());
+    for v in item.variants.iter() {
+        let name = attr::variant_name(v)?;
+        let key = |name: &str| match ignore_case {
+            true => name.to_ascii_lowercase(),
+            false => name.to_string(),
+        };
+        if names.iter().any(|n: &String| key(n) == key(&name)) {
+            return Err(Error::new(
+                v.span(),
+                format!("another variant is already named `{name}`"),
+            ));
+        }
+        let var = &v.ident;
+        if matches!(v.fields, syn::Fields::Unit) {
+            patterns.push(quote!(Self::#var));
+            units.0.push(var);
+            units.1.push(name.clone());
+        } else {
+            patterns.push(quote!(Self::#var(_)));
+            tuples.0.push(var);
+            tuples.1.push(name.clone());
+        }
+        names.push(name);
+    }
+    let (unit_vars, unit_names) = units;
+    let (tuple_vars, tuple_names) = tuples;
+
+    let mut token_stream = quote! {
+        impl #ident {
+            /// The name of the variant, given by `#[name = ".."]` or else its identifier.
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    #( #patterns => #names, )*
+                }
+            }
+        }
+    };
+
+    if options.display {
+        token_stream.extend(quote! {
+            impl core::fmt::Display for #ident {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    match self {
+                        #( Self::#unit_vars => f.pad(#unit_names), )*
+                        #( Self::#tuple_vars(n) => ::inttype_enum::__private::pad_call(f, #tuple_names, n), )*
+                    }
+                }
+            }
+        });
+    }
+
+    if options.from_str {
+        token_stream.extend(quote! {
+            impl core::str::FromStr for #ident {
+                type Err = ::inttype_enum::ParseError;
+
+                /// Parses a variant name, `Name(value)` for a variant with a field,
+                /// or a value in decimal, hexadecimal (`0x`) or binary (`0b`).
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    use ::inttype_enum::__private::{eq_name, parse_int, strip_call};
+                    let error = ::inttype_enum::ParseError::new(#type_name);
+                    #(
+                        if eq_name(s, #unit_names, #ignore_case) {
+                            return core::result::Result::Ok(Self::#unit_vars);
+                        }
+                    )*
+                    #(
+                        if let core::option::Option::Some(payload) = strip_call(s, #tuple_names, #ignore_case) {
+                            let value = Self::#tuple_vars(parse_int(payload).ok_or(error)?);
+                            return match ::inttype_enum::IntEnum::is_valid(&value) {
+                                true => core::result::Result::Ok(value),
+                                false => core::result::Result::Err(error),
+                            };
+                        }
+                    )*
+                    let value = parse_int::<#ty>(s).ok_or(error)?;
+                    Self::from_repr(value).ok_or(error)
+                }
+            }
+        });
+    }
+
+    Ok(token_stream)
+}
+
+/// The value of `*#expr`, an expression of type `&Enum`, without moving out of it
+fn value_of_ref(item: &ItemEnum, expr: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ident = &item.ident;
+    let arms = item.variants.iter().map(|v| {
+        let var = &v.ident;
+        match v.fields {
+            syn::Fields::Unit => {
+                let value = value_const(var);
+                quote!(#ident::#var => #ident::#value)
+            }
+            _ => quote!(#ident::#var(n) => *n),
+        }
+    });
+    quote! {
+        match #expr {
+            #( #arms, )*
+        }
+    }
+}
+
+/// `PartialEq` and/or `PartialOrd` between the enum and `ty`, both ways, as asked by `options`.
+/// `PartialOrd` needs `PartialEq`, so `partial_ord` implements both.
+fn compare_impls(item: &ItemEnum, ty: &Type, options: &attr::Options) -> proc_macro2::TokenStream {
+    let ident = &item.ident;
+    let mut token_stream = quote!();
+    if options.partial_eq || options.partial_ord {
+        let value = value_of_ref(item, quote!(self));
+        let other = value_of_ref(item, quote!(other));
+        token_stream.extend(quote! {
+            impl core::cmp::PartialEq<#ty> for #ident {
+                fn eq(&self, other: &#ty) -> bool {
+                    let value: #ty = #value;
+                    value == *other
+                }
+            }
+
+            impl core::cmp::PartialEq<#ident> for #ty {
+                fn eq(&self, other: &#ident) -> bool {
+                    let other: #ty = #other;
+                    *self == other
+                }
+            }
+        });
+    }
+    if options.partial_ord {
+        let value = value_of_ref(item, quote!(self));
+        let other = value_of_ref(item, quote!(other));
+        token_stream.extend(quote! {
+            impl core::cmp::PartialOrd<#ty> for #ident {
+                fn partial_cmp(&self, other: &#ty) -> core::option::Option<core::cmp::Ordering> {
+                    let value: #ty = #value;
+                    core::cmp::PartialOrd::partial_cmp(&value, other)
+                }
+            }
+
+            impl core::cmp::PartialOrd<#ident> for #ty {
+                fn partial_cmp(&self, other: &#ident) -> core::option::Option<core::cmp::Ordering> {
+                    let other: #ty = #other;
+                    core::cmp::PartialOrd::partial_cmp(self, &other)
+                }
+            }
+        });
+    }
+    token_stream
+}
+
+/// `Serialize` and `Deserialize` as the value of the variant, or for `options.serde_by_name`,
+/// as its `Display` and `FromStr` forms in human-readable formats.
+/// Nothing without `options.serde`.
+fn serde_impls(item: &ItemEnum, ty: &Type, options: &attr::Options) -> proc_macro2::TokenStream {
+    if !options.serde {
+        return quote!();
+    }
+    let ident = &item.ident;
+    let value = value_of_ref(item, quote!(self));
+    let (serialize_by_name, deserialize_by_name) = if options.serde_by_name {
+        (
+            quote! {
+                if serializer.is_human_readable() {
+                    return serializer.collect_str(self);
+                }
+            },
+            quote! {
+                if deserializer.is_human_readable() {
+                    return deserializer.deserialize_any(::inttype_enum::__private::NameVisitor::<Self>::new());
+                }
+            },
+        )
+    } else {
+        (quote!(), quote!())
+    };
+
+    quote! {
+        impl ::inttype_enum::__private::serde::Serialize for #ident {
+            fn serialize<__S>(&self, serializer: __S) -> core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: ::inttype_enum::__private::serde::Serializer,
+            {
+                #serialize_by_name
+                let value: #ty = #value;
+                ::inttype_enum::__private::serde::Serialize::serialize(&value, serializer)
+            }
+        }
+
+        impl<'de> ::inttype_enum::__private::serde::Deserialize<'de> for #ident {
+            fn deserialize<__D>(deserializer: __D) -> core::result::Result<Self, __D::Error>
+            where
+                __D: ::inttype_enum::__private::serde::Deserializer<'de>,
+            {
+                #deserialize_by_name
+                let value = <#ty as ::inttype_enum::__private::serde::Deserialize>::deserialize(deserializer)?;
+                // the error of a `From` conversion is `Infallible`
+                <Self as TryFrom<#ty>>::try_from(value)
+                    .map_err(<__D::Error as ::inttype_enum::__private::serde::de::Error>::custom)
+            }
+        }
+    }
+}
+
+/// Emits `const` items for the bounds of a range that is not made of integer literals,
+/// returns the names of its inclusive `(start, end)` bounds.
+fn const_range(
+    range: &ExprRange,
+    ty: &Type,
+    index: usize,
+    consts: &mut Vec<proc_macro2::TokenStream>,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let start_ident = format_ident!("__START_{}", index);
+    let end_ident = format_ident!("__END_{}", index);
+
+    let start = match &range.start {
+        Some(start) => quote!((#start)),
+        None => quote!(<#ty>::MIN),
+    };
+    let end = match (&range.end, &range.limits) {
+        (Some(end), syn::RangeLimits::Closed(_)) => quote!((#end)),
+        (Some(end), syn::RangeLimits::HalfOpen(_)) => quote!((#end) - 1),
+        (None, _) => quote!(<#ty>::MAX),
+    };
+
+    consts.push(quote! {
+        const #start_ident: #ty = #start;
+        const #end_ident: #ty = #end;
+    });
+    (quote!(#start_ident), quote!(#end_ident))
+}
+
+/// The values no range in `bounds` claims, worked out at compile time since some bounds are
+/// constants: a `GAPS` constant holding up to one more range than `bounds` and how many it
+/// holds, and an expression for each of those ranges
+fn fallback_gaps(
+    ty: &Type,
+    bounds: &[(proc_macro2::TokenStream, proc_macro2::TokenStream, &Ident)],
+) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
+    let count = bounds.len();
+    let slots = count + 1;
+    let starts = bounds.iter().map(|(start, _, _)| start);
+    let ends = bounds.iter().map(|(_, end, _)| end);
+    let gaps = quote! {
+        const GAPS: ([(#ty, #ty); #slots], usize) = {
+            let mut claimed = [#( (#starts, #ends) ),*];
+            // insertion sort by start
+            let mut i = 1;
+            while i < #count {
+                let mut j = i;
+                while j > 0 && claimed[j - 1].0 > claimed[j].0 {
+                    let swap = claimed[j - 1];
+                    claimed[j - 1] = claimed[j];
+                    claimed[j] = swap;
+                    j -= 1;
+                }
+                i += 1;
+            }
+            let mut gaps = [(<#ty>::MIN, <#ty>::MIN); #slots];
+            let mut len = 0;
+            // the smallest value not claimed yet, `None` once `MAX` is
+            let mut next = core::option::Option::Some(<#ty>::MIN);
+            let mut i = 0;
+            while i < #count {
+                if let core::option::Option::Some(n) = next {
+                    if claimed[i].0 > n {
+                        gaps[len] = (n, claimed[i].0 - 1);
+                        len += 1;
+                    }
+                }
+                next = claimed[i].1.checked_add(1);
+                i += 1;
+            }
+            if let core::option::Option::Some(n) = next {
+                gaps[len] = (n, <#ty>::MAX);
+                len += 1;
+            }
+            (gaps, len)
+        };
+    };
+    let gap_ranges = (0..slots)
+        .map(|i| quote!(GAPS.0[#i].0..=GAPS.0[#i].1))
+        .collect();
+    (gaps, gap_ranges)
+}
+
+/// Generates compile time assertions that ranges whose bounds are only known at compile time
+/// are not empty and don't overlap any other range.
+fn const_range_checks(
+    ident: &Ident,
+    bounds: &[(proc_macro2::TokenStream, proc_macro2::TokenStream, &Ident)],
+    const_ranges: &[usize],
+    range_consts: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let mut checks = Vec::new();
+    for &i in const_ranges {
+        let (start, end, var) = &bounds[i];
+        let msg = format!("`{ident}`: the range of `{var}` is empty");
+        checks.push(quote! {
+            if #start > #end {
+                core::panic!(#msg);
+            }
+        });
+
+        // pairs of literal ranges are already checked by `RangeChecker`
+        for (j, (other_start, other_end, other_var)) in bounds.iter().enumerate() {
+            if j == i || (const_ranges.contains(&j) && j < i) {
+                continue;
+            }
+            let msg =
+                format!("`{ident}`: the range of `{var}` overlaps the range of `{other_var}`");
+            checks.push(quote! {
+                if #start <= #other_end && #other_start <= #end {
+                    core::panic!(#msg);
+                }
+            });
+        }
+    }
+
+    quote! {
+        const _: () = {
+            #(#range_consts)*
+            #(#checks)*
+        };
+    }
+}
+
+/// Generates the error type returned by `TryFrom<#ty> for #ident`, and by `TryFrom<T>`
+/// for the other integer types `T`, which is why it is generic over the type of the value.
+///
+/// `ranges` is a block of code that evaluates to `&'static [RangeInclusive<#ty>]`,
+/// the values that do convert successfully.
+fn try_from_error(
+    item: &ItemEnum,
+    ty: &Type,
+    ranges: proc_macro2::TokenStream,
+) -> (Ident, proc_macro2::TokenStream) {
+    let vis = &item.vis;
+    let ident = &item.ident;
+    let name = ident.to_string();
+    let ty_name = ty.to_token_stream().to_string();
+    let error = Ident::new(&format!("TryFrom{name}Error"), ident.span());
+    let doc = format!("The error type returned when a checked conversion into [`{name}`] fails.");
+
+    let token_stream = quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        #vis struct #error<T = #ty> {
+            value: T,
+            out_of_range: bool,
+        }
+
+        impl<__T: Copy> #error<__T> {
+            /// The value that has no matching variant.
+            pub const fn value(&self) -> __T {
+                self.value
+            }
+        }
+
+        impl<__T> #error<__T> {
+            /// Whether the value doesn't even fit in the integer type of the enum,
+            /// rather than fitting without a matching variant.
+            pub const fn is_out_of_range(&self) -> bool {
+                self.out_of_range
+            }
+            /// The name of the enum the conversion was attempted into.
+            pub const fn type_name(&self) -> &'static str {
+                #name
+            }
+            /// The values that would have converted successfully.
+            pub fn ranges(&self) -> &'static [core::ops::RangeInclusive<#ty>] {
+                #ranges
+            }
+        }
+
+        impl<__T: core::fmt::Debug> core::fmt::Debug for #error<__T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!(#error))
+                    .field("value", &self.value)
+                    .field("out_of_range", &self.out_of_range)
+                    .field("type_name", &self.type_name())
+                    .field("ranges", &self.ranges())
+                    .finish()
+            }
+        }
+
+        impl<__T: core::fmt::Display> core::fmt::Display for #error<__T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                if self.out_of_range {
+                    return core::write!(f, "invalid value `{}` for `{}`, out of the range of `{}`", self.value, #name, #ty_name);
+                }
+                core::write!(f, "invalid value `{}` for `{}`, expected one of: ", self.value, #name)?;
+                for (i, r) in self.ranges().iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    if r.start() == r.end() {
+                        core::write!(f, "{}", r.start())?;
+                    } else {
+                        core::write!(f, "{}..={}", r.start(), r.end())?;
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl<__T: core::fmt::Debug + core::fmt::Display> core::error::Error for #error<__T> {}
+
+        impl From<#error> for #ty {
+            fn from(value: #error) -> Self {
+                value.value
+            }
+        }
+    };
+
+    (error, token_stream)
+}
+
+/// `validate_slice`, `decode_slice` and `decode_iter`. For one byte types the values are
+/// checked with a bitmap worked out at compile time by `from_repr`, before converting the
+/// valid ones.
+fn slice_impls(item: &ItemEnum, ty: &Type, error: &Ident) -> proc_macro2::TokenStream {
+    let ident = &item.ident;
+    let (bitmap, is_valid, decode) = match primitive_name(ty) {
+        Some("u8" | "i8") => {
+            // an associated const, so no generic parameter of the fns below shadows `Self`
+            let bitmap = quote! {
+                const __VALID: [u64; 4] = {
+                    let mut valid = [0u64; 4];
+                    let mut byte = 0;
+                    while byte < 256 {
+                        if Self::from_repr(byte as u8 as #ty).is_some() {
+                            valid[byte / 64] |= 1 << (byte % 64);
+                        }
+                        byte += 1;
+                    }
+                    valid
+                };
+            };
+            let is_valid = quote! {{
+                let byte = value as u8 as usize;
+                Self::__VALID[byte / 64] & (1 << (byte % 64)) != 0
+            }};
+            let decode = quote! {
+                if #is_valid {
+                    Self::from_repr(value)
+                } else {
+                    core::option::Option::None
+                }
+            };
+            (bitmap, is_valid, decode)
+        }
+        _ => (
+            quote!(),
+            quote!(Self::from_repr(value).is_some()),
+            quote!(Self::from_repr(value)),
+        ),
+    };
+
+    quote! {
+        impl #ident {
+            #bitmap
+
+            /// Checks that every value converts into a variant, or gives the index and value
+            /// of the first that doesn't.
+            pub fn validate_slice(values: &[#ty]) -> core::result::Result<(), (usize, #ty)> {
+                for (i, &value) in values.iter().enumerate() {
+                    if !#is_valid {
+                        return core::result::Result::Err((i, value));
+                    }
+                }
+                core::result::Result::Ok(())
+            }
+
+            /// Converts every value into `out`, or gives the index and value of the first that
+            /// doesn't convert, after converting the ones before it.
+            ///
+            /// # Panics
+            ///
+            /// If `values` and `out` have different lengths.
+            pub fn decode_slice(values: &[#ty], out: &mut [Self]) -> core::result::Result<(), (usize, #ty)> {
+                core::assert_eq!(values.len(), out.len(), "`values` and `out` have different lengths");
+                for (i, (&value, out)) in values.iter().zip(out).enumerate() {
+                    match #decode {
+                        core::option::Option::Some(v) => *out = v,
+                        core::option::Option::None => return core::result::Result::Err((i, value)),
+                    }
+                }
+                core::result::Result::Ok(())
+            }
+
+            /// Converts every value like `TryFrom` does, lazily.
+            pub fn decode_iter<I: core::iter::IntoIterator<Item = #ty>>(
+                values: I,
+            ) -> impl core::iter::Iterator<Item = core::result::Result<Self, #error>> {
+                values.into_iter().map(|value| match #decode {
+                    core::option::Option::Some(v) => core::result::Result::Ok(v),
+                    core::option::Option::None => core::result::Result::Err(#error { value, out_of_range: false }),
+                })
+            }
+        }
+    }
+}
+
+/// `from_be_bytes` and friends, `TryFrom<&[u8]>` reading big-endian, and for `options.io`
+/// `read_from` and `write_to`. Failed conversions report `error`.
+fn bytes_impls(
+    item: &ItemEnum,
+    ty: &Type,
+    options: &attr::Options,
+    error: &Ident,
+) -> proc_macro2::TokenStream {
+    let ident = &item.ident;
+    let mut token_stream = quote! {
+        impl #ident {
+            /// Converts the big-endian bytes of a value into the matching variant.
+            pub const fn from_be_bytes(bytes: [u8; core::mem::size_of::<#ty>()]) -> core::option::Option<Self> {
+                Self::from_repr(<#ty>::from_be_bytes(bytes))
+            }
+            /// Converts the little-endian bytes of a value into the matching variant.
+            pub const fn from_le_bytes(bytes: [u8; core::mem::size_of::<#ty>()]) -> core::option::Option<Self> {
+                Self::from_repr(<#ty>::from_le_bytes(bytes))
+            }
+            /// Converts the native-endian bytes of a value into the matching variant.
+            pub const fn from_ne_bytes(bytes: [u8; core::mem::size_of::<#ty>()]) -> core::option::Option<Self> {
+                Self::from_repr(<#ty>::from_ne_bytes(bytes))
+            }
+            /// The value of the variant as big-endian bytes.
+            pub const fn to_be_bytes(self) -> [u8; core::mem::size_of::<#ty>()] {
+                self.to_repr().to_be_bytes()
+            }
+            /// The value of the variant as little-endian bytes.
+            pub const fn to_le_bytes(self) -> [u8; core::mem::size_of::<#ty>()] {
+                self.to_repr().to_le_bytes()
+            }
+            /// The value of the variant as native-endian bytes.
+            pub const fn to_ne_bytes(self) -> [u8; core::mem::size_of::<#ty>()] {
+                self.to_repr().to_ne_bytes()
+            }
+        }
+
+        impl<'a> TryFrom<&'a [u8]> for #ident {
+            type Error = ::inttype_enum::FromBytesError<#error>;
+
+            /// Reads the value in big-endian, the slice must be exactly as long as the integer type.
+            fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+                let Ok(bytes) = <[u8; core::mem::size_of::<#ty>()]>::try_from(bytes) else {
+                    return core::result::Result::Err(::inttype_enum::FromBytesError::Length {
+                        expected: core::mem::size_of::<#ty>(),
+                        found: bytes.len(),
+                    });
+                };
+                let value = <#ty>::from_be_bytes(bytes);
+                match Self::from_repr(value) {
+                    core::option::Option::Some(v) => core::result::Result::Ok(v),
+                    core::option::Option::None => core::result::Result::Err(
+                        ::inttype_enum::FromBytesError::Value(#error { value, out_of_range: false }),
+                    ),
+                }
+            }
+        }
+    };
+
+    if options.io {
+        token_stream.extend(quote! {
+            impl #ident {
+                /// Reads a value in the byte order `endian` and converts it into the matching variant,
+                /// an invalid value is an error of kind `InvalidData`.
+                pub fn read_from<R: ::std::io::Read + ?Sized>(
+                    reader: &mut R,
+                    endian: ::inttype_enum::Endian,
+                ) -> ::std::io::Result<Self> {
+                    let mut bytes = [0u8; core::mem::size_of::<#ty>()];
+                    reader.read_exact(&mut bytes)?;
+                    let value = match endian {
+                        ::inttype_enum::Endian::Big => <#ty>::from_be_bytes(bytes),
+                        ::inttype_enum::Endian::Little => <#ty>::from_le_bytes(bytes),
+                        ::inttype_enum::Endian::Native => <#ty>::from_ne_bytes(bytes),
+                    };
+                    match Self::from_repr(value) {
+                        core::option::Option::Some(v) => core::result::Result::Ok(v),
+                        core::option::Option::None => core::result::Result::Err(::std::io::Error::new(
+                            ::std::io::ErrorKind::InvalidData,
+                            #error { value, out_of_range: false },
+                        )),
+                    }
+                }
+
+                /// Writes the value of the variant in the byte order `endian`.
+                pub fn write_to<W: ::std::io::Write + ?Sized>(
+                    self,
+                    writer: &mut W,
+                    endian: ::inttype_enum::Endian,
+                ) -> ::std::io::Result<()> {
+                    let bytes = match endian {
+                        ::inttype_enum::Endian::Big => self.to_be_bytes(),
+                        ::inttype_enum::Endian::Little => self.to_le_bytes(),
+                        ::inttype_enum::Endian::Native => self.to_ne_bytes(),
+                    };
+                    writer.write_all(&bytes)
+                }
+            }
+        });
+    }
+
+    token_stream
+}
+
+/// `From<#ident>` for the integer types that hold every value of `ty`, and `TryFrom<T>` from
+/// every other integer type `T`, failing with `error` when the value doesn't fit in `ty`.
+/// Opt-in, an unsuffixed literal in `try_from(0)` no longer infers `ty` once there are more.
+fn int_conversions(
+    item: &ItemEnum,
+    ty: &Type,
+    options: &attr::Options,
+    error: &Ident,
+) -> proc_macro2::TokenStream {
+    let ident = &item.ident;
+    let (true, Some(name)) = (options.int_conversions, primitive_name(ty)) else {
+        return quote!();
+    };
+    let wider = util::lossless_targets(name)
+        .iter()
+        .map(|t| format_ident!("{}", t));
+    let others = util::PRIMITIVES
+        .iter()
+        .filter(|t| **t != name)
+        .map(|t| format_ident!("{}", t));
+    quote! {
+        #(
+            impl From<#ident> for #wider {
+                fn from(value: #ident) -> Self {
+                    Self::from(value.to_repr())
+                }
+            }
+        )*
+        #(
+            impl TryFrom<#others> for #ident {
+                type Error = #error<#others>;
+
+                fn try_from(value: #others) -> Result<Self, Self::Error> {
+                    match <#ty>::try_from(value) {
+                        core::result::Result::Ok(v) => match Self::from_repr(v) {
+                            core::option::Option::Some(v) => core::result::Result::Ok(v),
+                            core::option::Option::None => core::result::Result::Err(#error { value, out_of_range: false }),
+                        },
+                        core::result::Result::Err(_) => core::result::Result::Err(#error { value, out_of_range: true }),
+                    }
+                }
+            }
+        )*
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pointer_width() {
+        let item: ItemEnum = syn::parse_quote! {
+            #[repr(usize)]
+            enum A {
+                #[range(..0x10000)]
+                Low(usize),
+                #[range(0x10000..=0xffffffff)]
+                High(usize),
+            }
+        };
+        let ty: Type = syn::parse_quote!(usize);
+        let options = attr::Options::default();
+
+        let expanded = int_range(&item, &options, &ty, &syn::parse_quote!(u16)).to_string();
+        assert!(expanded.contains("compile_error"));
+
+        let expanded = int_range(&item, &options, &ty, &syn::parse_quote!(u32)).to_string();
+        assert!(expanded.contains("0 ..= 65535 , 65536 ..= 4294967295"));
+        assert!(expanded.contains("impl From < usize > for A"));
+        assert!(!expanded.contains("impl TryFrom"));
+
+        let expanded = int_range(&item, &options, &ty, &syn::parse_quote!(u64)).to_string();
+        assert!(expanded.contains("0 ..= 65535 , 65536 ..= 4294967295"));
+        assert!(expanded.contains("impl TryFrom < usize > for A"));
+    }
+
+    #[test]
+    fn test_value_consts() {
+        let vars: [Ident; 3] = [
+            syn::parse_quote!(FooBar),
+            syn::parse_quote!(Baz),
+            syn::parse_quote!(Foo_Bar),
+        ];
+        let consts = value_consts(&[&vars[0], &vars[1]]).unwrap();
+        assert_eq!(consts, ["FOO_BAR_VALUE", "BAZ_VALUE"]);
+
+        let err = value_consts(&vars.iter().collect::<Vec<_>>()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Foo_Bar` and `FooBar` both have their value in `FOO_BAR_VALUE`, rename one of them"
+        );
+    }
+
+    #[test]
+    fn test_is_fns() {
+        let vars: [Ident; 3] = [
+            syn::parse_quote!(FooBar),
+            syn::parse_quote!(Baz),
+            syn::parse_quote!(Foo_Bar),
+        ];
+        let fns = is_fns(&[&vars[0], &vars[1]]).unwrap();
+        assert_eq!(fns, ["is_foo_bar", "is_baz"]);
+
+        let err = is_fns(&vars.iter().collect::<Vec<_>>()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Foo_Bar` and `FooBar` would both get `is_foo_bar`, rename one of them"
+        );
+
+        let item: ItemEnum = syn::parse_quote! {
+            #[repr(u8)]
+            enum A {
+                Valid = 0,
+                #[range(1..)]
+                Invalid(u8),
+            }
+        };
+        let ty: Type = syn::parse_quote!(u8);
+        let expanded = int_range(&item, &attr::Options::default(), &ty, &ty).to_string();
+        assert!(!expanded.contains("compile_error"));
+
+        let options = attr::Options {
+            accessors: true,
+            ..Default::default()
+        };
+        let expanded = int_range(&item, &options, &ty, &ty).to_string();
+        assert!(expanded.contains("compile_error"));
+        assert!(expanded.contains("`Valid` would get `is_valid`"));
+        assert!(expanded.contains("pub const VALID_VALUE"));
+        assert!(expanded.contains("impl From < u8 > for A"));
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{
+    attr::Lookup,
+    util::{primitive_name, unsigned_name},
+};
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Error, ItemEnum, Type};
+
+/// The values `start..=end` convert into the variant at `variant`, in declaration order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Entry {
+    pub start: i128,
+    pub end: i128,
+    pub variant: usize,
+}
+
+/// With fewer entries a `match` is as fast as a lookup, and no slower to compile
+const MIN_ENTRIES: usize = 16;
+/// The longest table `lookup = "table"` generates
+const MAX_TABLE: u128 = 1 << 16;
+/// Values are dense when the span from the smallest to the largest takes at most this many
+/// slots per value
+const DENSITY: u128 = 4;
+
+/// The lookup `from_repr` uses: the requested one, otherwise a `match` for few, non-literal
+/// or dense single values, a table for dense ranges and a binary search for sparse ones.
+/// Dense single values compile into a jump table that beats a lookup table, while a `match`
+/// on ranges compiles into comparisons, see `benches/lookup.rs`.
+/// `entries` is `None` when some value is not an integer literal.
+pub(crate) fn resolve(
+    requested: Option<Lookup>,
+    entries: Option<&[Entry]>,
+    span: Span,
+) -> Result<Lookup, Error> {
+    let entries = match (requested, entries) {
+        (Some(Lookup::Match), _) => return Ok(Lookup::Match),
+        (Some(lookup), None) => {
+            let name = match lookup {
+                Lookup::Table => "table",
+                _ => "search",
+            };
+            return Err(Error::new(
+                span,
+                format!("`lookup = \"{name}\"` requires every discriminant and range to be an integer literal"),
+            ));
+        }
+        (None, None) => return Ok(Lookup::Match),
+        (_, Some([])) => return Ok(Lookup::Match),
+        (_, Some(entries)) => entries,
+    };
+    let table_len = table_len(entries);
+    match requested {
+        Some(Lookup::Table) if table_len.is_none_or(|len| len > MAX_TABLE) => Err(Error::new(
+            span,
+            format!("the values span more than {MAX_TABLE} slots for `lookup = \"table\"`, try `lookup = \"search\"`"),
+        )),
+        Some(lookup) => Ok(lookup),
+        None if entries.len() < MIN_ENTRIES => Ok(Lookup::Match),
+        None => {
+            let values = entries.iter().fold(0u128, |sum, e| {
+                sum.saturating_add(e.end.abs_diff(e.start)).saturating_add(1)
+            });
+            match table_len {
+                Some(len) if len <= DENSITY.saturating_mul(values) => {
+                    if entries.iter().all(|e| e.start == e.end) {
+                        Ok(Lookup::Match)
+                    } else if len <= MAX_TABLE {
+                        Ok(Lookup::Table)
+                    } else {
+                        Ok(Lookup::Search)
+                    }
+                }
+                _ => Ok(Lookup::Search),
+            }
+        }
+    }
+}
+
+/// The number of values from the smallest to the largest, `None` if it overflows
+fn table_len(entries: &[Entry]) -> Option<u128> {
+    let min = entries.iter().map(|e| e.start).min()?;
+    let max = entries.iter().map(|e| e.end).max()?;
+    max.checked_sub(min)?.checked_add(1).map(|len| len as u128)
+}
+
+/// The body of `const fn from_repr(value: ty) -> Option<Self>` for a table or a search,
+/// `unmatched` is returned for the values no entry holds
+pub(crate) fn from_repr(
+    lookup: Lookup,
+    item: &ItemEnum,
+    ty: &Type,
+    entries: &[Entry],
+    unmatched: &TokenStream,
+) -> TokenStream {
+    let constructors = item
+        .variants
+        .iter()
+        .map(|v| {
+            let var = &v.ident;
+            match v.fields {
+                syn::Fields::Unit => quote!(Self::#var),
+                _ => quote!(Self::#var(value)),
+            }
+        })
+        .collect::<Vec<_>>();
+    // slot `0` is for no variant
+    let index_ty = match constructors.len() {
+        0..=0xfe => quote!(u8),
+        0xff..=0xfffe => quote!(u16),
+        _ => quote!(u32),
+    };
+    let mut variants = entries.iter().map(|e| e.variant).collect::<Vec<_>>();
+    variants.sort_unstable();
+    variants.dedup();
+    let slots = variants.iter().map(|v| Literal::usize_unsuffixed(v + 1));
+    let constructors = variants.iter().map(|v| &constructors[*v]);
+
+    let find = match lookup {
+        Lookup::Table => table(ty, entries, &index_ty),
+        _ => search(ty, entries, &index_ty),
+    };
+
+    quote! {
+        let slot: #index_ty = { #find };
+        match slot {
+            #( #slots => core::option::Option::Some(#constructors), )*
+            _ => #unmatched,
+        }
+    }
+}
+
+/// The smallest and largest value, if the entries hold every value between them
+pub(crate) fn contiguous(entries: &[Entry]) -> Option<(i128, i128)> {
+    let mut entries = entries.to_vec();
+    entries.sort_unstable_by_key(|e| e.start);
+    let first = entries.first()?;
+    let mut end = first.end;
+    for e in &entries[1..] {
+        if e.start != end.checked_add(1)? {
+            return None;
+        }
+        end = e.end;
+    }
+    Some((first.start, end))
+}
+
+/// The body of `const fn from_repr(value: ty) -> Option<Self>` for a field-less enum
+/// represented by `ty`, whose discriminants are all of `start..=end`
+pub(crate) fn transmute(
+    item: &ItemEnum,
+    ty: &Type,
+    start: i128,
+    end: i128,
+    unmatched: &TokenStream,
+) -> TokenStream {
+    let vars = item.variants.iter().map(|v| &v.ident);
+    let min = Literal::i128_unsuffixed(start);
+    let last = Literal::u128_unsuffixed((end as u128).wrapping_sub(start as u128));
+    let unsigned = format_ident!("{}", unsigned_name(primitive_name(ty).unwrap()));
+
+    quote! {
+        // the variants are only made by the transmute, which the dead code lint can't see
+        let _: &[Self] = &[#(Self::#vars),*];
+        const MIN: #ty = #min;
+        // values below `MIN` wrap around past the end
+        if (value as #unsigned).wrapping_sub(MIN as #unsigned) <= #last {
+            // SAFETY: the enum is `#[repr(#ty)]` without fields, and `value` is one of its
+            // discriminants
+            core::option::Option::Some(unsafe { core::mem::transmute::<#ty, Self>(value) })
+        } else {
+            #unmatched
+        }
+    }
+}
+
+/// Finds the slot of `value` by indexing an array of every value from the smallest
+fn table(ty: &Type, entries: &[Entry], index_ty: &TokenStream) -> TokenStream {
+    let min = entries.iter().map(|e| e.start).min().unwrap();
+    let len = table_len(entries).unwrap() as usize;
+    let mut slots = vec![0usize; len];
+    for e in entries {
+        let start = (e.start - min) as usize;
+        let end = (e.end - min) as usize;
+        slots[start..=end].fill(e.variant + 1);
+    }
+    let slots = slots.into_iter().map(Literal::usize_unsuffixed);
+    let min = Literal::i128_unsuffixed(min);
+    let last = Literal::usize_unsuffixed(len - 1);
+    let unsigned = format_ident!("{}", unsigned_name(primitive_name(ty).unwrap()));
+
+    quote! {
+        const MIN: #ty = #min;
+        const TABLE: [#index_ty; #len] = [#(#slots),*];
+        // values below `MIN` wrap around past the end
+        let offset = (value as #unsigned).wrapping_sub(MIN as #unsigned);
+        if offset <= #last {
+            TABLE[offset as usize]
+        } else {
+            0
+        }
+    }
+}
+
+/// Finds the slot of `value` by searching the sorted entries, only those starting in the
+/// same bucket of values: there are about as many buckets as entries, so most hold one
+fn search(ty: &Type, entries: &[Entry], index_ty: &TokenStream) -> TokenStream {
+    let mut entries = entries.to_vec();
+    entries.sort_unstable_by_key(|e| e.start);
+    let len = entries.len();
+    let min = entries[0].start;
+    let last = entries.iter().map(|e| e.end).max().unwrap();
+    // the offset of the largest value, as an unsigned integer
+    let last = (last as u128).wrapping_sub(min as u128);
+    let shift = (0..128).find(|k| (last >> k) < len as u128).unwrap();
+    let bucket_count = (last >> shift) as usize + 1;
+    // the number of entries starting before each bucket, and one past the last
+    let mut firsts = vec![len; bucket_count + 1];
+    for (i, e) in entries.iter().enumerate().rev() {
+        let bucket = ((e.start as u128).wrapping_sub(min as u128) >> shift) as usize;
+        firsts[..=bucket].fill(i);
+    }
+
+    let first_ty = match len {
+        0..=0xffff => quote!(u16),
+        _ => quote!(u32),
+    };
+    let firsts = firsts.into_iter().map(Literal::usize_unsuffixed);
+    let firsts_len = bucket_count + 1;
+    let starts = entries.iter().map(|e| Literal::i128_unsuffixed(e.start));
+    let slots = entries
+        .iter()
+        .map(|e| Literal::usize_unsuffixed(e.variant + 1));
+    // single values only need to be compared with the start
+    let ends = match entries.iter().all(|e| e.start == e.end) {
+        true => quote!(STARTS),
+        false => {
+            let ends = entries.iter().map(|e| Literal::i128_unsuffixed(e.end));
+            quote! {{
+                const ENDS: [#ty; #len] = [#(#ends),*];
+                ENDS
+            }}
+        }
+    };
+    let min = Literal::i128_unsuffixed(min);
+    let last = Literal::u128_unsuffixed(last);
+    let shift = Literal::u32_unsuffixed(shift);
+    let unsigned = format_ident!("{}", unsigned_name(primitive_name(ty).unwrap()));
+
+    quote! {
+        const MIN: #ty = #min;
+        const FIRSTS: [#first_ty; #firsts_len] = [#(#firsts),*];
+        const STARTS: [#ty; #len] = [#(#starts),*];
+        const SLOTS: [#index_ty; #len] = [#(#slots),*];
+        // values below `MIN` wrap around past the end
+        let offset = (value as #unsigned).wrapping_sub(MIN as #unsigned);
+        if offset > #last {
+            0
+        } else {
+            let bucket = (offset >> #shift) as usize;
+            let first = FIRSTS[bucket] as usize;
+            // the last entry starting at or below `value`, one that starts in an earlier
+            // bucket if none in this one does
+            let mut i = FIRSTS[bucket + 1] as usize;
+            while i > first && STARTS[i - 1] > value {
+                i -= 1;
+            }
+            if i > 0 && value <= #ends[i - 1] {
+                SLOTS[i - 1]
+            } else {
+                0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(values: &[(i128, i128)]) -> Vec<Entry> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(variant, &(start, end))| Entry {
+                start,
+                end,
+                variant,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let span = Span::call_site();
+        let dense = entries(&(0..600).map(|n| (n, n)).collect::<Vec<_>>());
+        let sparse = entries(&(0..600).map(|n| (n * 1000, n * 1000)).collect::<Vec<_>>());
+        let few = entries(&[(0, 0), (1, 10), (200, 255)]);
+        let wide = entries(&[(i128::MIN, 0), (1, i128::MAX)]);
+        let ranges = entries(&(0..48).map(|n| (n * 50, n * 50 + 39)).collect::<Vec<_>>());
+        let sparse_ranges = entries(&(0..48).map(|n| (n * 500, n * 500 + 39)).collect::<Vec<_>>());
+        let wide_ranges = entries(
+            &(0..48)
+                .map(|n| (n << 20, (n << 20) + (1 << 19)))
+                .collect::<Vec<_>>(),
+        );
+
+        assert_eq!(resolve(None, Some(&dense), span).unwrap(), Lookup::Match);
+        assert_eq!(resolve(None, Some(&sparse), span).unwrap(), Lookup::Search);
+        assert_eq!(resolve(None, Some(&ranges), span).unwrap(), Lookup::Table);
+        assert_eq!(
+            resolve(None, Some(&sparse_ranges), span).unwrap(),
+            Lookup::Search
+        );
+        assert_eq!(
+            resolve(None, Some(&wide_ranges), span).unwrap(),
+            Lookup::Search
+        );
+        assert_eq!(resolve(None, Some(&few), span).unwrap(), Lookup::Match);
+        assert_eq!(resolve(None, None, span).unwrap(), Lookup::Match);
+        assert_eq!(resolve(None, Some(&[]), span).unwrap(), Lookup::Match);
+
+        let requested = |lookup, entries| resolve(Some(lookup), entries, span);
+        assert_eq!(
+            requested(Lookup::Match, Some(&dense)).unwrap(),
+            Lookup::Match
+        );
+        assert_eq!(
+            requested(Lookup::Search, Some(&dense)).unwrap(),
+            Lookup::Search
+        );
+        assert_eq!(requested(Lookup::Table, Some(&few)).unwrap(), Lookup::Table);
+        assert_eq!(
+            requested(Lookup::Search, Some(&wide)).unwrap(),
+            Lookup::Search
+        );
+        assert_eq!(requested(Lookup::Match, None).unwrap(), Lookup::Match);
+
+        assert!(requested(Lookup::Table, Some(&sparse)).is_err());
+        assert!(requested(Lookup::Table, Some(&wide)).is_err());
+        assert!(requested(Lookup::Table, None).is_err());
+        assert!(requested(Lookup::Search, None).is_err());
+    }
+
+    #[test]
+    fn test_contiguous() {
+        assert_eq!(
+            contiguous(&entries(&[(0, 0), (1, 1), (2, 2)])),
+            Some((0, 2))
+        );
+        assert_eq!(
+            contiguous(&entries(&[(3, 3), (1, 1), (2, 2)])),
+            Some((1, 3))
+        );
+        assert_eq!(contiguous(&entries(&[(-1, -1), (0, 5)])), Some((-1, 5)));
+        assert_eq!(contiguous(&entries(&[(7, 7)])), Some((7, 7)));
+        assert_eq!(
+            contiguous(&entries(&[
+                (i128::MAX - 1, i128::MAX - 1),
+                (i128::MAX, i128::MAX)
+            ])),
+            Some((i128::MAX - 1, i128::MAX))
+        );
+
+        assert_eq!(contiguous(&entries(&[(0, 0), (2, 2)])), None);
+        assert_eq!(contiguous(&entries(&[(0, 0), (0, 0)])), None);
+        assert_eq!(contiguous(&[]), None);
+    }
+}
@@ -0,0 +1,505 @@
+use core::ops::RangeInclusive;
+
+use crate::int_range_ext::*;
+use syn::{spanned::Spanned, Error, ExprRange, Type, TypePath};
+
+/// Evaluates an integer literal, optionally negated, like `42`, `0x2a` or `-1`
+fn expr_to_int<T: core::str::FromStr>(expr: &syn::Expr) -> Result<T, Error>
+where
+    <T as core::str::FromStr>::Err: core::fmt::Display,
+{
+    match expr {
+        syn::Expr::Lit(lit) => match &lit.lit {
+            syn::Lit::Int(int) => int
+                .base10_parse::<T>()
+                .map_err(|e| Error::new(int.span(), format!("{e}"))),
+            _ => Err(Error::new(expr.span(), "only integer literal allowed here")),
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr: inner,
+            ..
+        }) => match inner.as_ref() {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(int),
+                ..
+            }) => format!("-{}", int.base10_digits())
+                .parse::<T>()
+                .map_err(|e| Error::new(expr.span(), format!("{e}"))),
+            _ => Err(Error::new(expr.span(), "only integer literal allowed here")),
+        },
+        syn::Expr::Group(group) => expr_to_int(&group.expr),
+        syn::Expr::Paren(paren) => expr_to_int(&paren.expr),
+        _ => Err(Error::new(expr.span(), "only literal allowed here")),
+    }
+}
+
+/// Whether `expr` is an integer literal that `expr_to_int` can evaluate
+fn is_int_literal(expr: &syn::Expr) -> bool {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(_),
+            ..
+        }) => true,
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => matches!(
+            expr.as_ref(),
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(_),
+                ..
+            })
+        ),
+        syn::Expr::Group(group) => is_int_literal(&group.expr),
+        syn::Expr::Paren(paren) => is_int_literal(&paren.expr),
+        _ => false,
+    }
+}
+
+/// The value of the integer literal `expr`, `None` if it isn't one or doesn't fit `i128`
+pub(crate) fn literal_value(expr: &syn::Expr) -> Option<i128> {
+    match is_int_literal(expr) {
+        true => expr_to_int(expr).ok(),
+        false => None,
+    }
+}
+
+/// Whether every bound of `expr` is an integer literal, so the range can be checked by
+/// `RangeChecker`. Other ranges are checked by the compiler instead.
+pub(crate) fn is_literal_range(expr: &ExprRange) -> bool {
+    expr.start
+        .iter()
+        .chain(expr.end.iter())
+        .all(|e| is_int_literal(e))
+}
+
+fn expr_to_range<T: Integer + core::str::FromStr>(
+    expr: &ExprRange,
+) -> Result<RangeInclusive<T>, Error>
+where
+    <T as core::str::FromStr>::Err: core::fmt::Display,
+{
+    let start = match &expr.start {
+        Some(expr) => expr_to_int(expr)?,
+        None => T::MIN,
+    };
+
+    let end = match &expr.end {
+        Some(expr) => expr_to_int(expr)?,
+        None => T::MAX,
+    };
+
+    // println!("expr: {} {:?}", expr.into_token_stream(), expr.span());
+
+    match &expr.limits {
+        syn::RangeLimits::HalfOpen(_dot_dot) => {
+            if expr.end.is_none() {
+                if (start..=end).is_empty() {
+                    return Err(Error::new(expr.span(), "range is empty"));
+                }
+                Ok(start..=end)
+            } else {
+                let r = (start..end)
+                    .to_inclusive()
+                    .map_err(|_| Error::new(expr.span(), "range is empty"))?;
+                Ok(r)
+            }
+        }
+        syn::RangeLimits::Closed(_dot_dot_eq) => {
+            if (start..=end).is_empty() {
+                return Err(Error::new(expr.span(), "range is empty"));
+            }
+            Ok(start..=end)
+        }
+    }
+}
+
+/// https://doc.rust-lang.org/reference/type-layout.html#primitive-representations
+pub(crate) const PRIMITIVES: [&str; 12] = [
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// The unsigned integer type as wide as `name`
+pub(crate) fn unsigned_name(name: &str) -> &'static str {
+    match name {
+        "u8" | "i8" => "u8",
+        "u16" | "i16" => "u16",
+        "u32" | "i32" => "u32",
+        "u64" | "i64" => "u64",
+        "u128" | "i128" => "u128",
+        _ => "usize",
+    }
+}
+
+/// The integer types with a lossless `From<name>` in std, other than `name` itself
+pub(crate) fn lossless_targets(name: &str) -> &'static [&'static str] {
+    match name {
+        "u8" => &[
+            "u16", "u32", "u64", "u128", "usize", "i16", "i32", "i64", "i128", "isize",
+        ],
+        "u16" => &["u32", "u64", "u128", "usize", "i32", "i64", "i128"],
+        "u32" => &["u64", "u128", "i64", "i128"],
+        "u64" => &["u128", "i128"],
+        "i8" => &["i16", "i32", "i64", "i128", "isize"],
+        "i16" => &["i32", "i64", "i128", "isize"],
+        "i32" => &["i64", "i128"],
+        "i64" => &["i128"],
+        _ => &[],
+    }
+}
+
+/// The name of the primitive integer type `ty` refers to, like `u8` for both `u8` and
+/// `::core::primitive::u8`. Type aliases can't be resolved by a macro, they give `None`.
+pub(crate) fn primitive_name(ty: &Type) -> Option<&'static str> {
+    let path = match ty {
+        Type::Path(TypePath { qself: None, path }) => path,
+        Type::Group(group) => return primitive_name(&group.elem),
+        Type::Paren(paren) => return primitive_name(&paren.elem),
+        _ => return None,
+    };
+    if path.segments.iter().any(|s| !s.arguments.is_none()) {
+        return None;
+    }
+    let segments = path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect::<Vec<_>>();
+    let name = match segments.as_slice() {
+        [name] if path.leading_colon.is_none() => name,
+        [krate, primitive, name]
+            if (krate == "core" || krate == "std") && primitive == "primitive" =>
+        {
+            name
+        }
+        _ => return None,
+    };
+    PRIMITIVES.iter().find(|p| **p == name).copied()
+}
+
+/// `HttpVersion`, `HTTPVersion` and `Http_Version` all become `http_version`
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let chars = name.trim_start_matches("r#").chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(chars.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 && !snake.ends_with('_') {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Keeps track of the values of an integer type that no range has claimed yet
+#[derive(Debug)]
+pub(crate) enum RangeChecker {
+    U8(RangeSubtracter<u8>),
+    U16(RangeSubtracter<u16>),
+    U32(RangeSubtracter<u32>),
+    U64(RangeSubtracter<u64>),
+    U128(RangeSubtracter<u128>),
+    Usize(RangeSubtracter<usize>),
+    I8(RangeSubtracter<i8>),
+    I16(RangeSubtracter<i16>),
+    I32(RangeSubtracter<i32>),
+    I64(RangeSubtracter<i64>),
+    I128(RangeSubtracter<i128>),
+    Isize(RangeSubtracter<isize>),
+}
+
+/// Runs `$body` with `$subtracter` bound to the inner `RangeSubtracter<T>`,
+/// and `T` to its integer type.
+macro_rules! dispatch {
+    ($checker: expr, $subtracter: ident => $body: expr) => {
+        match $checker {
+            RangeChecker::U8($subtracter) => dispatch!(@ u8, $body),
+            RangeChecker::U16($subtracter) => dispatch!(@ u16, $body),
+            RangeChecker::U32($subtracter) => dispatch!(@ u32, $body),
+            RangeChecker::U64($subtracter) => dispatch!(@ u64, $body),
+            RangeChecker::U128($subtracter) => dispatch!(@ u128, $body),
+            RangeChecker::Usize($subtracter) => dispatch!(@ usize, $body),
+            RangeChecker::I8($subtracter) => dispatch!(@ i8, $body),
+            RangeChecker::I16($subtracter) => dispatch!(@ i16, $body),
+            RangeChecker::I32($subtracter) => dispatch!(@ i32, $body),
+            RangeChecker::I64($subtracter) => dispatch!(@ i64, $body),
+            RangeChecker::I128($subtracter) => dispatch!(@ i128, $body),
+            RangeChecker::Isize($subtracter) => dispatch!(@ isize, $body),
+        }
+    };
+    (@ $ty: ident, $body: expr) => {{
+        #[allow(unused)]
+        type T = $ty;
+        $body
+    }};
+}
+
+impl RangeChecker {
+    /// Starts with every value of `ty` unclaimed, `ty` must be a primitive integer type
+    pub fn new(ty: &Type) -> Result<Self, Error> {
+        let Some(name) = primitive_name(ty) else {
+            return Err(Error::new(
+                ty.span(),
+                format!(
+                    "unsupported type, expected one of `{}`. Type aliases are not supported",
+                    PRIMITIVES.join(", ")
+                ),
+            ));
+        };
+        Ok(match name {
+            "u8" => Self::U8(RangeSubtracter::new(..).unwrap()),
+            "u16" => Self::U16(RangeSubtracter::new(..).unwrap()),
+            "u32" => Self::U32(RangeSubtracter::new(..).unwrap()),
+            "u64" => Self::U64(RangeSubtracter::new(..).unwrap()),
+            "u128" => Self::U128(RangeSubtracter::new(..).unwrap()),
+            "usize" => Self::Usize(RangeSubtracter::new(..).unwrap()),
+            "i8" => Self::I8(RangeSubtracter::new(..).unwrap()),
+            "i16" => Self::I16(RangeSubtracter::new(..).unwrap()),
+            "i32" => Self::I32(RangeSubtracter::new(..).unwrap()),
+            "i64" => Self::I64(RangeSubtracter::new(..).unwrap()),
+            "i128" => Self::I128(RangeSubtracter::new(..).unwrap()),
+            "isize" => Self::Isize(RangeSubtracter::new(..).unwrap()),
+            _ => unreachable!("every primitive is listed"),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        dispatch!(self, subtracter => subtracter.is_empty())
+    }
+
+    pub fn substract(&mut self, expr: &ExprRange) -> Result<(), Error> {
+        dispatch!(self, subtracter => {
+            let r = expr_to_range::<T>(expr)?;
+            subtracter
+                .substract(&r)
+                .map_err(|_| Error::new(expr.span(), "range duplicated"))
+        })
+    }
+
+    /// The values no range has claimed yet, as inclusive ranges.
+    pub fn remaining(&self) -> Vec<ExprRange> {
+        dispatch!(self, subtracter => {
+            subtracter
+                .ranges()
+                .iter()
+                .map(|r| syn::parse_str::<ExprRange>(format!("{r:?}").as_str()).unwrap())
+                .collect()
+        })
+    }
+
+    /// Marks every value as claimed.
+    pub fn clear(&mut self) {
+        dispatch!(self, subtracter => subtracter.clear())
+    }
+
+    /// Like `expr_to_inclusive_expr`, but as a match pattern. With `open`, bounds at the limits
+    /// of the type are left out: the compiler only treats `usize`/`isize` matches as exhaustive
+    /// with half-open patterns.
+    pub fn expr_to_pattern(
+        &self,
+        expr: &ExprRange,
+        open: bool,
+    ) -> Result<proc_macro2::TokenStream, Error> {
+        dispatch!(self, _subtracter => {
+            let r = expr_to_range::<T>(expr)?;
+            let (start, end) = (*r.start(), *r.end());
+            let pattern = match (open && start == T::MIN, open && end == T::MAX) {
+                (true, true) => "_".to_string(),
+                (true, false) => format!("..={end}"),
+                (false, true) => format!("{start}.."),
+                (false, false) => format!("{start}..={end}"),
+            };
+            Ok(syn::parse_str(&pattern)?)
+        })
+    }
+
+    pub fn expr_to_inclusive_expr(&self, expr: &ExprRange) -> Result<ExprRange, Error> {
+        dispatch!(self, _subtracter => {
+            let r = expr_to_range::<T>(expr)?;
+            Ok(syn::parse_str::<ExprRange>(format!("{r:?}").as_str())?)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    #[test]
+    fn test_expr_to_range() {
+        let expr = syn::parse_str::<ExprRange>("1..=10").unwrap();
+        let r = expr_to_range::<u32>(&expr).unwrap();
+        assert_eq!(r, 1..=10);
+
+        let expr = syn::parse_str::<ExprRange>("1..10").unwrap();
+        let r = expr_to_range::<u32>(&expr).unwrap();
+        assert_eq!(r, 1..=9);
+
+        let expr = syn::parse_str::<ExprRange>("1..=1").unwrap();
+        let r = expr_to_range::<u32>(&expr).unwrap();
+        assert_eq!(r, 1..=1);
+
+        let expr = syn::parse_str::<ExprRange>("1..=0").unwrap();
+        let r = expr_to_range::<u32>(&expr);
+        assert!(r.is_err());
+
+        let expr = syn::parse_str::<ExprRange>("1..1").unwrap();
+        let r = expr_to_range::<u32>(&expr);
+        assert!(r.is_err());
+
+        let expr = syn::parse_str::<ExprRange>("1..=1").unwrap();
+        let r = expr_to_range::<u32>(&expr).unwrap();
+        assert_eq!(r, 1..=1);
+
+        let expr = syn::parse_str::<ExprRange>("1..=1").unwrap();
+        let r = expr_to_range::<u8>(&expr).unwrap();
+        assert_eq!(r, 1..=1);
+
+        let expr = syn::parse_str::<ExprRange>("..10").unwrap();
+        let r = expr_to_range::<u8>(&expr).unwrap();
+        assert_eq!(r, 0..=9);
+
+        let expr = syn::parse_str::<ExprRange>("..").unwrap();
+        let r = expr_to_range::<u8>(&expr).unwrap();
+        assert_eq!(r, 0..=255);
+
+        let expr = syn::parse_str::<ExprRange>("-128..0").unwrap();
+        let r = expr_to_range::<i8>(&expr).unwrap();
+        assert_eq!(r, -128..=-1);
+
+        let expr = syn::parse_str::<ExprRange>("- 1..=- 1").unwrap();
+        let r = expr_to_range::<i16>(&expr).unwrap();
+        assert_eq!(r, -1..=-1);
+
+        let expr = syn::parse_str::<ExprRange>("-0x10..=0x10").unwrap();
+        let r = expr_to_range::<i32>(&expr).unwrap();
+        assert_eq!(r, -16..=16);
+
+        let expr =
+            syn::parse_str::<ExprRange>("..-170141183460469231731687303715884105727").unwrap();
+        let r = expr_to_range::<i128>(&expr).unwrap();
+        assert_eq!(r, i128::MIN..=i128::MIN);
+
+        let expr = syn::parse_str::<ExprRange>("-1..").unwrap();
+        assert!(expr_to_range::<u8>(&expr).is_err());
+
+        let expr = syn::parse_str::<ExprRange>("-129..0").unwrap();
+        assert!(expr_to_range::<i8>(&expr).is_err());
+    }
+
+    #[test]
+    fn test_primitive_name() {
+        let name = |ty: &str| primitive_name(&syn::parse_str::<Type>(ty).unwrap());
+        assert_eq!(name("u8"), Some("u8"));
+        assert_eq!(name("isize"), Some("isize"));
+        assert_eq!(name("core::primitive::u16"), Some("u16"));
+        assert_eq!(name("::core::primitive::i32"), Some("i32"));
+        assert_eq!(name("std::primitive::u128"), Some("u128"));
+        assert_eq!(name("::std::primitive::i8"), Some("i8"));
+
+        assert_eq!(name("::u8"), None);
+        assert_eq!(name("primitive::u8"), None);
+        assert_eq!(name("alloc::primitive::u8"), None);
+        assert_eq!(name("core::primitive::f32"), None);
+        assert_eq!(name("MyInt"), None);
+        assert_eq!(name("&u8"), None);
+    }
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Connect"), "connect");
+        assert_eq!(to_snake_case("NotFound"), "not_found");
+        assert_eq!(to_snake_case("HTTPVersion"), "http_version");
+        assert_eq!(to_snake_case("Http_Version"), "http_version");
+        assert_eq!(to_snake_case("V2Reply"), "v2_reply");
+        assert_eq!(to_snake_case("A"), "a");
+        assert_eq!(to_snake_case("r#Type"), "type");
+    }
+
+    #[test]
+    fn test_checker_types() {
+        let checker = RangeChecker::new(&syn::parse_quote!(::core::primitive::i16)).unwrap();
+        assert!(matches!(checker, RangeChecker::I16(_)));
+        assert!(RangeChecker::new(&syn::parse_quote!(MyInt)).is_err());
+        assert!(RangeChecker::new(&syn::parse_quote!(f32)).is_err());
+    }
+
+    #[test]
+    fn test_checker() {
+        let mut checker = RangeChecker::new(&syn::parse_quote!(u8)).unwrap();
+        println!("{:?}", checker);
+
+        let expr = syn::parse_str::<ExprRange>("1..=10").unwrap();
+        checker.substract(&expr).unwrap();
+        println!("{:?}", checker);
+
+        let expr = syn::parse_str::<ExprRange>("5..=15").unwrap();
+        assert!(checker.substract(&expr).is_err());
+
+        let expr = syn::parse_str::<ExprRange>("20..30").unwrap();
+        checker.substract(&expr).unwrap();
+        println!("{:?}", checker);
+
+        checker
+            .substract(&syn::parse_str::<ExprRange>("0..=0").unwrap())
+            .unwrap();
+        println!("{:?}", checker);
+
+        checker
+            .substract(&syn::parse_str::<ExprRange>("30..").unwrap())
+            .unwrap();
+        println!("{:?}", checker);
+
+        checker
+            .substract(&syn::parse_str::<ExprRange>("11..20").unwrap())
+            .unwrap();
+        println!("{:?}", checker);
+
+        assert!(checker.is_empty());
+    }
+
+    #[test]
+    fn test_checker_remaining() {
+        let mut checker = RangeChecker::new(&syn::parse_quote!(u8)).unwrap();
+        checker
+            .substract(&syn::parse_str::<ExprRange>("1..=10").unwrap())
+            .unwrap();
+        checker
+            .substract(&syn::parse_str::<ExprRange>("20..30").unwrap())
+            .unwrap();
+
+        let remaining = checker
+            .remaining()
+            .iter()
+            .map(|r| r.to_token_stream().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, ["0 ..= 0", "11 ..= 19", "30 ..= 255"]);
+
+        checker.clear();
+        assert!(checker.is_empty());
+        assert!(checker.remaining().is_empty());
+    }
+
+    #[test]
+    fn test_expr_to_pattern() {
+        let checker = RangeChecker::new(&syn::parse_quote!(i8)).unwrap();
+        let pattern = |expr: &str, open: bool| {
+            let expr = syn::parse_str::<ExprRange>(expr).unwrap();
+            let pattern = checker.expr_to_pattern(&expr, open).unwrap().to_string();
+            pattern.replace(' ', "")
+        };
+        assert_eq!(pattern("1..10", false), "1..=9");
+        assert_eq!(pattern("1..10", true), "1..=9");
+        assert_eq!(pattern("..0", false), "-128..=-1");
+        assert_eq!(pattern("..0", true), "..=-1");
+        assert_eq!(pattern("0..", false), "0..=127");
+        assert_eq!(pattern("0..", true), "0..");
+        assert_eq!(pattern("..", true), "_");
+    }
+}
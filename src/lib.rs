@@ -1,375 +1,308 @@
+#![no_std]
 #![doc=include_str!("../README.md")]
 
-mod int_range_ext;
-mod util;
-use util::RangeChecker;
+use core::ops::RangeInclusive;
 
-use proc_macro::TokenStream;
-use quote::{quote, ToTokens};
-use syn::{parse_macro_input, spanned::Spanned, Error, ExprRange, ItemEnum, Meta, Type};
+pub use inttype_enum_derive::{IntRange, IntType};
 
-#[proc_macro_derive(IntType, attributes(default,))]
-pub fn inttype(input: TokenStream) -> TokenStream {
-    let item = parse_macro_input!(input as ItemEnum);
+/// An enum that converts to and from an integer type,
+/// implemented by both `#[derive(IntType)]` and `#[derive(IntRange)]`.
+pub trait IntEnum: Sized {
+    /// The integer type the enum converts to and from
+    type Repr: Copy + Eq + Ord + core::hash::Hash + core::fmt::Debug + core::fmt::Display;
 
-    let ident = &item.ident;
-    let Some(ty) = item.attrs.iter().find_map(|attr| {
-        let Meta::List(ref meta_list) = attr.meta else {
-            return None;
-        };
-        if !attr.path().is_ident("repr") {
-            return None;
-        }
+    /// The number of variants of the enum
+    const VARIANT_COUNT: usize;
 
-        syn::parse2::<Type>(meta_list.tokens.clone()).ok()
-    }) else {
-        // https://doc.rust-lang.org/reference/type-layout.html#primitive-representations
-        return Error::new(item.span(), "no #[repr(inttype)] provided.\n`inttype` can be one of `u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, and isize`.\n See https://doc.rust-lang.org/reference/type-layout.html#primitive-representations")
-        .into_compile_error().into();
-    };
-
-    let mut default_var = None;
-    let mut result = None;
-    let var = item
-        .variants
-        .iter()
-        .map(|v| {
-            if v.attrs.iter().any(|attr| attr.path().is_ident("default")) {
-                if default_var.is_some() {
-                    result = Some(
-                        Error::new(
-                            v.span(),
-                            "Multiple default variables supplied! should be only one!",
-                        )
-                        .into_compile_error(),
-                    );
-                }
-                default_var = Some(&v.ident);
-            }
+    /// Converts `value` into the enum like `From`/`TryFrom` does, `None` if it has no variant
+    fn from_repr(value: Self::Repr) -> Option<Self>;
 
-            if !matches!(v.fields, syn::Fields::Unit) {
-                result = Some(
-                    Error::new(v.span(), "every variant must be Unit kind, like `None`")
-                        .into_compile_error(),
-                );
-            }
-            &v.ident
-        })
-        .collect::<Vec<_>>();
+    /// Converts the enum into its integer value
+    fn to_repr(self) -> Self::Repr;
+
+    /// Whether `self` converts back into itself after converting to its integer value,
+    /// e.g. `false` for a payload outside its variant's range
+    fn is_valid(&self) -> bool;
+
+    /// Every value that converts into a variant
+    fn values() -> impl Iterator<Item = Self::Repr>;
+}
+
+/// An enum whose variants own ranges of integer values, implemented by `#[derive(IntRange)]`.
+pub trait IntRangeEnum: IntEnum {
+    /// The ranges of values that convert into the enum, in the order of the variants
+    /// but with the `#[fallback]` variant's last: its discriminant, then every value no range claims
+    fn ranges() -> &'static [RangeInclusive<Self::Repr>];
+}
+
+/// The error returned by the `FromStr` implementation of `#[inttype(from_str)]`,
+/// when the string is neither a variant name nor a value that converts into a variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    type_name: &'static str,
+}
 
-    if let Some(ret) = result {
-        return ret.into();
+impl ParseError {
+    #[doc(hidden)]
+    pub const fn new(type_name: &'static str) -> Self {
+        Self { type_name }
     }
 
-    let mut token_stream = quote! {
-        impl From<#ident> for #ty {
-            fn from(value: #ident) -> Self {
-                value as Self
-            }
-        }
-    };
-
-    let from = if let Some(default_var) = default_var {
-        quote! {
-            impl From<#ty> for #ident {
-                fn from(value: #ty) -> Self {
-                    #![allow(non_upper_case_globals)]
-                    #(
-                        const #var: #ty = #ident::#var as #ty;
-                    )*
-                    match value {
-                        #( #var => Self::#var, )*
-                        _ => Self::#default_var,
-                    }
-                }
+    /// The name of the enum the string was parsed into.
+    pub const fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "no `{}` variant has this name or value", self.type_name)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// The error returned by `TryFrom<&[u8]>`, when the slice has the wrong length or
+/// holds a value that doesn't convert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromBytesError<E> {
+    /// The slice is not exactly as long as the integer type
+    Length { expected: usize, found: usize },
+    /// The error of converting the decoded value
+    Value(E),
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for FromBytesError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Length { expected, found } => {
+                write!(f, "expected {expected} bytes, found {found}")
             }
+            Self::Value(e) => e.fmt(f),
         }
-    } else {
-        quote! {
-            impl TryFrom<#ty> for #ident {
-                type Error = #ty;
-
-                fn try_from(value: #ty) -> Result<Self, Self::Error> {
-                    #![allow(non_upper_case_globals)]
-                    #(
-                        const #var: #ty = #ident::#var as #ty;
-                    )*
-                    match value {
-                        #( #var => Ok(Self::#var), )*
-                        _ => Err(value)
-                    }
-                }
-            }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for FromBytesError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Length { .. } => None,
+            Self::Value(e) => Some(e),
         }
-    };
+    }
+}
 
-    token_stream.extend(from);
-    token_stream.into()
+/// The byte order of `read_from` and `write_to`, generated by `#[inttype(io)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    Big,
+    Little,
+    Native,
 }
 
-#[proc_macro_derive(IntRange, attributes(range,))]
-pub fn new_inttype(input: TokenStream) -> TokenStream {
-    let item = parse_macro_input!(input as ItemEnum);
+/// Used by the generated code, not public API.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "serde")]
+    pub use serde_support::NameVisitor;
+    /// `s` equals `name`, ignoring the ASCII case if asked to
+    pub fn eq_name(s: &str, name: &str, ignore_case: bool) -> bool {
+        if ignore_case {
+            s.eq_ignore_ascii_case(name)
+        } else {
+            s == name
+        }
+    }
 
-    let ident = &item.ident;
-    let Some(ty) = item.attrs.iter().find_map(|attr| {
-        let Meta::List(ref meta_list) = attr.meta else {
-            return None;
-        };
-        if !attr.path().is_ident("repr") {
+    /// The payload of `s` if it looks like `name(payload)`
+    pub fn strip_call<'a>(s: &'a str, name: &str, ignore_case: bool) -> Option<&'a str> {
+        let (head, rest) = s.split_at_checked(name.len())?;
+        if !eq_name(head, name, ignore_case) {
             return None;
         }
+        rest.strip_prefix('(')?.strip_suffix(')')
+    }
 
-        syn::parse2::<Type>(meta_list.tokens.clone()).ok()
-    }) else {
-        // https://doc.rust-lang.org/reference/type-layout.html#primitive-representations
-        return Error::new(item.span(), "no #[repr(inttype)] provided.\n`inttype` can be one of `u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, and isize`.\nSee https://doc.rust-lang.org/reference/type-layout.html#primitive-representations")
-        .into_compile_error().into();
-    };
-
-    let ty_str = ty.to_token_stream().to_string();
-
-    let mut checker = RangeChecker::new(ty_str.clone()).unwrap();
-    // let mut variants = Vec::with_capacity(item.variants.len());
-    let mut unit_variants = Vec::with_capacity(item.variants.len());
-    let mut unit_discriminant = Vec::with_capacity(item.variants.len());
-    let mut unnamed_variants = Vec::with_capacity(item.variants.len());
-    let mut unnamed_ranges = Vec::with_capacity(item.variants.len());
-    let mut ranges = Vec::with_capacity(item.variants.len());
-
-    for v in item.variants.iter() {
-        match &v.fields {
-            syn::Fields::Named(_) => {
-                return Error::new(
-                    v.fields.span(),
-                    "variant can only be Unit/Unamed kind, Examples: A=0,B(u8),",
-                )
-                .into_compile_error()
-                .into()
-            }
-            //#[repr(u8)] #[derive(IntType)] enum { #[range(1..5)]a(u8), }
-            syn::Fields::Unnamed(fields) => {
-                if fields.unnamed.len() != 1 {
-                    return Error::new(
-                        fields.span(),
-                        format!(
-                            "Unnamed variant can only have 1 field, here it must be {}",
-                            ty.into_token_stream()
-                        ),
-                    )
-                    .into_compile_error()
-                    .into();
-                }
-                if v.discriminant.is_some() {
-                    return Error::new(
-                        v.discriminant.as_ref().unwrap().1.span(),
-                        "Unnamed variant can't have discriminant",
-                    )
-                    .into_compile_error()
-                    .into();
-                }
-                for unamed in fields.unnamed.iter() {
-                    if unamed.ty.to_token_stream().to_string() != ty_str {
-                        return Error::new(
-                            fields.span(),
-                            format!(
-                                "Unnamed variant's field must be the same type as its repr: {}",
-                                ty.into_token_stream()
-                            ),
-                        )
-                        .into_compile_error()
-                        .into();
-                    }
-                }
+    /// Writes `name(value)` the way `Formatter::pad` writes a string, honoring the width, fill,
+    /// alignment and precision of `f` without allocating
+    pub fn pad_call(
+        f: &mut core::fmt::Formatter<'_>,
+        name: &str,
+        value: impl core::fmt::Display,
+    ) -> core::fmt::Result {
+        use core::fmt::{Alignment, Write};
 
-                let mut range_cnt = 0;
-
-                for attr in v.attrs.iter() {
-                    if attr.path().is_ident("range") {
-                        range_cnt += 1;
-                        if range_cnt > 1 {
-                            return Error::new(
-                                attr.path().span(),
-                                "Only one range attribute must be provided for Unnamed variant",
-                            )
-                            .into_compile_error()
-                            .into();
-                        }
-                        let range: ExprRange = match attr.parse_args() {
-                            Ok(r) => r,
-                            Err(e) => return e.into_compile_error().into(),
-                        };
-
-                        // println!("cur ident: {}", v.ident.to_string());
-
-                        // println!("range: {}", range.to_token_stream());
-                        if let Err(e) = checker.substract(&range) {
-                            return e.into_compile_error().into();
-                        }
-                        let inclusive_expr = checker.expr_to_inclusive_expr(&range).unwrap();
-
-                        ranges.push(range);
-                        unnamed_variants.push(&v.ident);
-                        unnamed_ranges.push(inclusive_expr);
-                    }
-                }
-                if range_cnt != 1 {
-                    return Error::new(
-                        fields.span(),
-                        "one range attribute must be provided for Unnamed variant",
-                    )
-                    .into_compile_error()
-                    .into();
-                }
-            }
-            //#[repr(u8)] #[derive(IntType)] enum { a=0, }
-            syn::Fields::Unit => {
-                if v.attrs.iter().any(|attr| attr.path().is_ident("range")) {
-                    return Error::new(v.span(), "Unit variant should not have `range` attribute")
-                        .into_compile_error()
-                        .into();
-                }
-                // let s = v.ident.to_string();
-                // println!("v: {}", v.to_token_stream());
-                // println!("cur unit ident: {}", v.ident.to_string());
-                match v.discriminant.as_ref() {
-                    Some((_, n)) => {
-                        let s = n.to_token_stream().to_string();
-                        let range =
-                            syn::parse_str::<ExprRange>(format!("{}..={}", s, s).as_str()).unwrap();
-                        if let Err(e) = checker.substract(&range) {
-                            // println!("e.span(): {:?}", e.span());
-                            return Error::new(n.span(), e.to_string())
-                                .into_compile_error()
-                                .into();
-                        }
-                        ranges.push(range);
-                        unit_discriminant.push(n);
-                        unit_variants.push(&v.ident);
+        if f.width().is_none() && f.precision().is_none() {
+            return write!(f, "{name}({value})");
+        }
+
+        /// Passes on the first `limit` chars written to it, counting them
+        struct Chars<'a> {
+            out: Option<&'a mut dyn Write>,
+            count: usize,
+            limit: usize,
+        }
+
+        impl Write for Chars<'_> {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                for c in s.chars() {
+                    if self.count == self.limit {
+                        break;
                     }
-                    None => {
-                        return Error::new(v.span(), "must specify discriminant, like A=0")
-                            .into_compile_error()
-                            .into()
+                    if let Some(out) = self.out.as_mut() {
+                        out.write_char(c)?;
                     }
+                    self.count += 1;
                 }
+                Ok(())
             }
         }
-        // println!("ident: {}", v.ident.to_string());
+
+        let limit = f.precision().unwrap_or(usize::MAX);
+        let mut chars = Chars {
+            out: None,
+            count: 0,
+            limit,
+        };
+        write!(chars, "{name}({value})")?;
+        let padding = f.width().unwrap_or(0).saturating_sub(chars.count);
+        let (before, after) = match f.align() {
+            Some(Alignment::Right) => (padding, 0),
+            Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+            Some(Alignment::Left) | None => (0, padding),
+        };
+        let fill = f.fill();
+        for _ in 0..before {
+            f.write_char(fill)?;
+        }
+        let mut chars = Chars {
+            out: Some(f),
+            count: 0,
+            limit,
+        };
+        write!(chars, "{name}({value})")?;
+        for _ in 0..after {
+            f.write_char(fill)?;
+        }
+        Ok(())
     }
 
-    // println!("checker.is_empty(): {}", checker.is_empty());
-    // println!("ranges: {:?}", ranges.iter().map(|r| r.to_token_stream()).collect::<Vec<_>>());
-    // println!("ty: {}", ty.to_token_stream());
-    // println!("ident: {}", ident);
-    // println!("unit_variants: {:?}", unit_variants);
-    // println!("unit_discriminant: {:?}", unit_discriminant.iter().map(|r| r.to_token_stream()).collect::<Vec<_>>());
-    // println!("unnamed_variants: {:?}", unnamed_variants);
-    // println!("unnamed_ranges: {:?}", unnamed_ranges.iter().map(|r| r.to_token_stream()).collect::<Vec<_>>());
-
-    let all_ranges = ranges
-        .iter()
-        .map(|v| checker.expr_to_inclusive_expr(v).unwrap())
-        .collect::<Vec<_>>();
-
-    let mut token_stream = quote! {
-        impl From<#ident> for #ty {
-            fn from(value: #ident) -> Self {
-                match value {
-                    #(
-                        #ident::#unit_variants => #unit_discriminant,
-                    )*
-                    #(
-                        #ident::#unnamed_variants(n) => n,
-                    )*
-                }
+    /// Parses an integer literal in decimal, hexadecimal (`0x`) or binary (`0b`),
+    /// with an optional `-` sign
+    pub fn parse_int<T: TryFrom<u128> + TryFrom<i128>>(s: &str) -> Option<T> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let (radix, digits) = if let Some(digits) = s.strip_prefix("0x").or(s.strip_prefix("0X")) {
+            (16, digits)
+        } else if let Some(digits) = s.strip_prefix("0b").or(s.strip_prefix("0B")) {
+            (2, digits)
+        } else {
+            (10, s)
+        };
+        // `from_str_radix` would accept a second sign
+        if digits.starts_with(['+', '-']) {
+            return None;
+        }
+        let magnitude = u128::from_str_radix(digits, radix).ok()?;
+        if negative {
+            T::try_from(0i128.checked_sub_unsigned(magnitude)?).ok()
+        } else {
+            T::try_from(magnitude).ok()
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_support {
+        use crate::IntEnum;
+        use core::{fmt, marker::PhantomData, str::FromStr};
+        use serde::de::{Error, Unexpected, Visitor};
+
+        /// Deserializes a name with `FromStr`, or an integer value
+        pub struct NameVisitor<T>(PhantomData<T>);
+
+        impl<T> NameVisitor<T> {
+            #[allow(clippy::new_without_default)]
+            pub fn new() -> Self {
+                Self(PhantomData)
             }
         }
 
-        // impl PartialEq<#ident> for #ty {
-        //     fn eq(&self, other: &#ident) -> bool {
-        //         match other {
-        //             #(
-        //                 #ident::#unit_variants => #unit_discriminant == *self,
-        //             )*
-        //             #(
-        //                 #ident::#unnamed_variants(n) => *n == *self,
-        //             )*
-        //         }
-        //     }
-        // }
-        // impl PartialEq<#ty> for #ident {
-        //     fn eq(&self, other: &#ty) -> bool {
-        //         match self {
-        //             #(
-        //                 #ident::#unit_variants => #unit_discriminant == *other,
-        //             )*
-        //             #(
-        //                 #ident::#unnamed_variants(n) => *n == *other,
-        //             )*
-        //         }
-        //     }
-        // }
-
-        impl #ident {
-            pub fn ranges() -> &'static [core::ops::RangeInclusive<#ty>] {
-                &[#(#all_ranges,)*]
+        impl<'de, T> Visitor<'de> for NameVisitor<T>
+        where
+            T: IntEnum + FromStr,
+            T::Err: fmt::Display,
+            T::Repr: TryFrom<u64> + TryFrom<i64>,
+        {
+            type Value = T;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a variant name or value")
             }
-            pub fn is_valid(&self) -> bool {
-                match self {
-                    #(
-                        Self::#unit_variants => true,
-                    )*
-                    #(
-                        #[allow(unreachable_patterns)]
-                        #[allow(non_contiguous_range_endpoints)]
-                        Self::#unnamed_variants(n) => match n {
-                            #unnamed_ranges => true,
-                            _ => false,
-                        },
-                    )*
-                }
+
+            fn visit_str<E: Error>(self, v: &str) -> Result<T, E> {
+                v.parse().map_err(E::custom)
             }
-        }
-    };
-
-    let ty_to_ident = if checker.is_empty() {
-        quote! {
-            impl From<#ty> for #ident {
-                fn from(value: #ty) -> Self {
-                    match value {
-                        #(
-                            #unit_discriminant => Self::#unit_variants,
-                        )*
-                        #(
-                            #unnamed_ranges => Self::#unnamed_variants(value),
-                        )*
-                    }
-                }
+
+            fn visit_u64<E: Error>(self, v: u64) -> Result<T, E> {
+                T::Repr::try_from(v)
+                    .ok()
+                    .and_then(T::from_repr)
+                    .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
             }
-        }
-    } else {
-        quote! {
-            impl TryFrom<#ty> for #ident {
-                type Error = #ty;
-
-                fn try_from(value: #ty) -> Result<Self, Self::Error> {
-                    #[allow(unreachable_patterns)]
-                    #[allow(non_contiguous_range_endpoints)]
-                    match value {
-                        #(
-                            #unit_discriminant => Ok(Self::#unit_variants),
-                        )*
-                        #(
-                            #unnamed_ranges => Ok(Self::#unnamed_variants(value)),
-                        )*
-                        _ => Err(value)
-                    }
-                }
+
+            fn visit_i64<E: Error>(self, v: i64) -> Result<T, E> {
+                T::Repr::try_from(v)
+                    .ok()
+                    .and_then(T::from_repr)
+                    .ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
             }
         }
-    };
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
 
-    token_stream.extend(ty_to_ident);
-    token_stream.into()
+        #[test]
+        fn test_parse_int() {
+            assert_eq!(parse_int::<u8>("255"), Some(255));
+            assert_eq!(parse_int::<u8>("0xff"), Some(255));
+            assert_eq!(parse_int::<u8>("0XFF"), Some(255));
+            assert_eq!(parse_int::<u8>("0b101"), Some(5));
+            assert_eq!(parse_int::<i8>("-128"), Some(-128));
+            assert_eq!(parse_int::<i8>("-0x80"), Some(-128));
+            assert_eq!(
+                parse_int::<i128>("-170141183460469231731687303715884105728"),
+                Some(i128::MIN)
+            );
+            assert_eq!(
+                parse_int::<u128>("0xffffffffffffffffffffffffffffffff"),
+                Some(u128::MAX)
+            );
+            assert_eq!(parse_int::<u8>("-0"), Some(0));
+
+            assert_eq!(parse_int::<u8>("256"), None);
+            assert_eq!(parse_int::<u8>("-1"), None);
+            assert_eq!(parse_int::<i8>("128"), None);
+            assert_eq!(parse_int::<u8>("+1"), None);
+            assert_eq!(parse_int::<u8>("--1"), None);
+            assert_eq!(parse_int::<u8>("0x"), None);
+            assert_eq!(parse_int::<u8>(""), None);
+            assert_eq!(parse_int::<u8>("A"), None);
+        }
+
+        #[test]
+        fn test_strip_call() {
+            assert_eq!(strip_call("B(11)", "B", false), Some("11"));
+            assert_eq!(strip_call("b(0x0b)", "B", true), Some("0x0b"));
+            assert_eq!(strip_call("b(11)", "B", false), None);
+            assert_eq!(strip_call("B11", "B", false), None);
+            assert_eq!(strip_call("B(11", "B", false), None);
+            assert_eq!(strip_call("é(1)", "B", false), None);
+        }
+    }
 }
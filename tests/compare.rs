@@ -0,0 +1,49 @@
+// `impl PartialEq<Enum> for u8` makes `u8 == _` ambiguous in this whole crate, so these
+// enums get their own test crate
+use inttype_enum::*;
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+#[inttype(partial_eq)]
+enum Flag {
+    Off = 0,
+    On = 1,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i16)]
+#[inttype(partial_ord)]
+enum Reading {
+    Zero = 0,
+    #[range(1..)]
+    Positive(i16),
+    #[range(..0)]
+    Negative(i16),
+}
+
+#[test]
+fn compare() {
+    assert!(Flag::On == 1u8);
+    assert!(0u8 == Flag::Off);
+    assert!(Flag::On != 0u8);
+    // an unsuffixed literal can only be the integer type
+    assert!(Flag::On == 1);
+    assert!(0 == Flag::Off);
+    // comparisons between the enums and between integers still infer
+    assert!(Flag::On == Flag::On);
+    let raw = 1;
+    assert!(raw == 1);
+    assert!(u8::from(Flag::On) == 1);
+    assert!(Flag::try_from(raw).unwrap() == raw);
+    // `0u8 == Flag::On.into()` no longer infers the target of `into()`, name it instead
+    assert!(0u8 == u8::from(Flag::Off));
+    assert!(1u8 == Into::<u8>::into(Flag::On));
+
+    assert!(Reading::Positive(5) > 4i16);
+    assert!(Reading::Negative(-5) < Reading::ZERO_VALUE);
+    assert!(-6 < Reading::Negative(-5));
+    assert!(Reading::Zero == 0);
+    assert!(Reading::Zero >= 0);
+    assert!(0 <= Reading::Zero);
+    assert!(Reading::Positive(300) != 301);
+}
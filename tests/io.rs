@@ -0,0 +1,53 @@
+use inttype_enum::*;
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u16)]
+#[inttype(io)]
+enum Kind {
+    Data = 0x0102,
+    Ack = 0x0304,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i32)]
+#[inttype(io)]
+enum Offset {
+    Zero = 0,
+    #[range(1..)]
+    Forward(i32),
+}
+
+#[test]
+fn read_write() {
+    let mut buf = Vec::new();
+    Kind::Data.write_to(&mut buf, Endian::Big).unwrap();
+    Kind::Ack.write_to(&mut buf, Endian::Little).unwrap();
+    Offset::Forward(5)
+        .write_to(&mut buf, Endian::Native)
+        .unwrap();
+    assert_eq!(buf[..4], [1, 2, 4, 3]);
+
+    let mut reader = &buf[..];
+    assert_eq!(
+        Kind::read_from(&mut reader, Endian::Big).unwrap(),
+        Kind::Data
+    );
+    assert_eq!(
+        Kind::read_from(&mut reader, Endian::Little).unwrap(),
+        Kind::Ack
+    );
+    assert_eq!(
+        Offset::read_from(&mut reader, Endian::Native).unwrap(),
+        Offset::Forward(5)
+    );
+
+    let err = Kind::read_from(&mut reader, Endian::Big).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let err = Offset::read_from(&mut &(-1i32).to_be_bytes()[..], Endian::Big).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(
+        err.to_string(),
+        "invalid value `-1` for `Offset`, expected one of: 0, 1..=2147483647"
+    );
+}
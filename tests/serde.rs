@@ -0,0 +1,152 @@
+use inttype_enum::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+#[inttype(serde)]
+enum Cmd {
+    Connect = 1,
+    Bind = 2,
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u16)]
+#[inttype(serde)]
+enum Method {
+    Get = 1,
+    #[default]
+    Unknown = 0xffff,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i8)]
+#[inttype(serde)]
+enum Level {
+    Zero = 0,
+    #[range(1..10)]
+    Low(i8),
+    #[range(..0)]
+    Negative(i8),
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+#[inttype(serde, display, from_str, case_insensitive, serde_by_name)]
+enum Named {
+    #[name = "connect"]
+    Connect = 1,
+    #[other]
+    Unknown(u8),
+}
+
+/// Named like the generic parameters of the generated code
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(serde)]
+enum S {
+    A = 0,
+    #[range(1..)]
+    B(u8),
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+#[inttype(serde)]
+enum D {
+    A = 1,
+}
+
+// without `#[inttype(serde)]` serde's own derives are free to be used
+#[derive(Debug, PartialEq, Eq, IntType, Serialize, Deserialize)]
+#[repr(u8)]
+enum Plain {
+    First = 1,
+    Second = 2,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Header {
+    cmd: Cmd,
+    method: Method,
+    level: Level,
+    named: Named,
+}
+
+#[test]
+fn json() {
+    let header = Header {
+        cmd: Cmd::Bind,
+        method: Method::Get,
+        level: Level::Negative(-3),
+        named: Named::Unknown(7),
+    };
+    let json = serde_json::to_string(&header).unwrap();
+    assert_eq!(
+        json,
+        r#"{"cmd":2,"method":1,"level":-3,"named":"Unknown(7)"}"#
+    );
+    assert_eq!(serde_json::from_str::<Header>(&json).unwrap(), header);
+
+    assert_eq!(
+        serde_json::to_string(&Named::Connect).unwrap(),
+        r#""connect""#
+    );
+    assert_eq!(
+        serde_json::from_str::<Named>(r#""CONNECT""#).unwrap(),
+        Named::Connect
+    );
+    assert_eq!(serde_json::from_str::<Named>("1").unwrap(), Named::Connect);
+    assert_eq!(serde_json::from_str::<Named>("0x01").ok(), None);
+    assert!(serde_json::from_str::<Named>("256").is_err());
+    assert!(serde_json::from_str::<Named>(r#""Unknown(1)""#).is_err());
+
+    // the `#[default]` variant catches every unlisted value
+    assert_eq!(
+        serde_json::from_str::<Method>("7").unwrap(),
+        Method::Unknown
+    );
+    assert_eq!(serde_json::from_str::<Level>("5").unwrap(), Level::Low(5));
+
+    let err = serde_json::from_str::<Cmd>("3").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value `3` for `Cmd`, expected one of: 1, 2"
+    );
+    assert!(serde_json::from_str::<Cmd>("256").is_err());
+    assert!(serde_json::from_str::<Level>("10").is_err());
+
+    assert_eq!(
+        serde_json::to_string(&Plain::Second).unwrap(),
+        r#""Second""#
+    );
+    assert_eq!(
+        serde_json::from_str::<Plain>(r#""First""#).unwrap(),
+        Plain::First
+    );
+    assert_eq!(Plain::from_repr(2), Some(Plain::Second));
+
+    assert_eq!(serde_json::to_string(&S::B(3)).unwrap(), "3");
+    assert_eq!(serde_json::from_str::<S>("0").unwrap(), S::A);
+    assert_eq!(serde_json::from_str::<D>("1").unwrap(), D::A);
+    assert!(serde_json::from_str::<D>("2").is_err());
+}
+
+#[test]
+fn bincode() {
+    let header = Header {
+        cmd: Cmd::Connect,
+        method: Method::Unknown,
+        level: Level::Low(9),
+        named: Named::Connect,
+    };
+    let bytes = bincode::serialize(&header).unwrap();
+    // not human-readable, so `Named` is its value too
+    assert_eq!(bytes, [1, 0xff, 0xff, 9, 1]);
+    assert_eq!(bincode::deserialize::<Header>(&bytes).unwrap(), header);
+
+    assert!(bincode::deserialize::<Cmd>(&[0]).is_err());
+    assert_eq!(
+        bincode::deserialize::<Named>(&[9]).unwrap(),
+        Named::Unknown(9)
+    );
+}
@@ -1,3 +1,8 @@
+#![allow(
+    clippy::bool_assert_comparison,
+    clippy::unnecessary_fallible_conversions
+)]
+
 use inttype_enum::*;
 
 #[derive(Debug, PartialEq, Eq, IntType)]
@@ -8,6 +13,7 @@ enum Test {
 
 #[derive(Debug, PartialEq, Eq, IntRange)]
 #[repr(u8)]
+#[inttype(accessors)]
 enum Test2 {
     A = 0x00,
     #[range(1..16)]
@@ -35,7 +41,7 @@ enum Test4 {
 #[test]
 fn test() {
     assert_eq!(Test::try_from(255), Ok(Test::Hello));
-    assert_eq!(Test::try_from(0), Err(0));
+    assert_eq!(Test::try_from(0).map_err(u8::from), Err(0));
 
     assert!(0u8 == Test2::A.into());
     assert!(0u8 == u8::from(Test2::A));
@@ -53,6 +59,1185 @@ fn test() {
     assert_eq!(Test4::try_from(16), Ok(Test4::A(16)));
     assert_eq!(Test4::try_from(127), Ok(Test4::A(127)));
     assert_eq!(Test4::try_from(128), Ok(Test4::B(128)));
-    assert_eq!(Test4::try_from(255), Err(255));
+    assert_eq!(Test4::try_from(255).map_err(u8::from), Err(255));
     assert_eq!(Test4::ranges(), &[0..=127, 128..=254]);
 }
+
+#[test]
+fn try_from_error() {
+    let err = Test::try_from(0).unwrap_err();
+    assert_eq!(err.value(), 0);
+    assert_eq!(err.type_name(), "Test");
+    assert_eq!(err.ranges(), &[255..=255]);
+    assert_eq!(u8::from(err), 0);
+    assert_eq!(
+        err.to_string(),
+        "invalid value `0` for `Test`, expected one of: 255"
+    );
+
+    let err = Test4::try_from(255).unwrap_err();
+    assert_eq!(err.ranges(), Test4::ranges());
+    assert_eq!(
+        err.to_string(),
+        "invalid value `255` for `Test4`, expected one of: 0..=127, 128..=254"
+    );
+    assert_eq!(
+        format!("{err:?}"),
+        "TryFromTest4Error { value: 255, out_of_range: false, type_name: \"Test4\", ranges: [0..=127, 128..=254] }"
+    );
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert!(err.to_string().starts_with("invalid value `255`"));
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+enum Opcode {
+    Nop,
+    Push = 0x10,
+    Pop,
+    #[other]
+    Unknown(u8),
+    Halt = 0xff,
+}
+
+#[test]
+fn other() {
+    assert_eq!(u8::from(Opcode::Nop), 0);
+    assert_eq!(u8::from(Opcode::Pop), 0x11);
+    assert_eq!(u8::from(Opcode::Halt), 0xff);
+    assert_eq!(u8::from(Opcode::Unknown(0x42)), 0x42);
+
+    assert_eq!(Opcode::from(0x00), Opcode::Nop);
+    assert_eq!(Opcode::from(0x10), Opcode::Push);
+    assert_eq!(Opcode::from(0x11), Opcode::Pop);
+    assert_eq!(Opcode::from(0xff), Opcode::Halt);
+    assert_eq!(Opcode::from(0x42), Opcode::Unknown(0x42));
+
+    for n in 0..=u8::MAX {
+        assert_eq!(u8::from(Opcode::from(n)), n);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(accessors)]
+enum Registry {
+    A = 0x00,
+    #[range(rest)]
+    Unassigned(u8),
+    #[range(0x10..0x20)]
+    B(u8),
+    C = 0x80,
+}
+
+#[test]
+fn range_rest() {
+    assert_eq!(
+        Registry::ranges(),
+        &[0..=0, 1..=15, 32..=127, 129..=255, 16..=31, 128..=128]
+    );
+    assert_eq!(Registry::from(0x00), Registry::A);
+    assert_eq!(Registry::from(0x01), Registry::Unassigned(0x01));
+    assert_eq!(Registry::from(0x10), Registry::B(0x10));
+    assert_eq!(Registry::from(0x20), Registry::Unassigned(0x20));
+    assert_eq!(Registry::from(0x80), Registry::C);
+    assert_eq!(Registry::from(0xff), Registry::Unassigned(0xff));
+
+    assert!(Registry::Unassigned(0x7f).is_valid());
+    assert!(!Registry::Unassigned(0x10).is_valid());
+    assert!(!Registry::Unassigned(0x80).is_valid());
+
+    for n in 0..=u8::MAX {
+        assert_eq!(u8::from(Registry::from(n)), n);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i16)]
+#[inttype(accessors)]
+enum Temperature {
+    Unknown = -1000,
+    #[range(-999..-40)]
+    TooCold(i16),
+    #[range(-40..0)]
+    Freezing(i16),
+    Zero = 0,
+    #[range(1..=40)]
+    Mild(i16),
+    Boiling = 100,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i8)]
+enum Sign {
+    Zero = 0,
+    #[range(-128..0)]
+    Negative(i8),
+    #[range(1..)]
+    Positive(i8),
+}
+
+#[test]
+fn negative() {
+    assert_eq!(
+        Temperature::ranges(),
+        &[
+            -1000..=-1000,
+            -999..=-41,
+            -40..=-1,
+            0..=0,
+            1..=40,
+            100..=100
+        ]
+    );
+    assert_eq!(Temperature::try_from(-41), Ok(Temperature::TooCold(-41)));
+    assert_eq!(Temperature::try_from(-40), Ok(Temperature::Freezing(-40)));
+    assert_eq!(Temperature::try_from(-1), Ok(Temperature::Freezing(-1)));
+    assert_eq!(Temperature::try_from(0), Ok(Temperature::Zero));
+    assert_eq!(Temperature::try_from(100), Ok(Temperature::Boiling));
+    assert_eq!(Temperature::try_from(-1000), Ok(Temperature::Unknown));
+    assert_eq!(Temperature::try_from(-1001).map_err(i16::from), Err(-1001));
+    assert_eq!(Temperature::try_from(41).map_err(i16::from), Err(41));
+    assert_eq!(i16::from(Temperature::Unknown), -1000);
+    assert!(Temperature::Freezing(-40).is_valid());
+    assert!(!Temperature::Freezing(-41).is_valid());
+
+    assert_eq!(Sign::ranges(), &[0..=0, -128..=-1, 1..=127]);
+    assert_eq!(Sign::from(i8::MIN), Sign::Negative(i8::MIN));
+    assert_eq!(Sign::from(0), Sign::Zero);
+    assert_eq!(Sign::from(i8::MAX), Sign::Positive(i8::MAX));
+}
+
+mod proto {
+    pub const CONNECT: u8 = 1;
+    pub const MIN_USER: u8 = 0x80;
+    pub const MAX_USER: u8 = 0xf0;
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(accessors)]
+enum Message {
+    Connect = proto::CONNECT,
+    Disconnect = proto::CONNECT + 1,
+    #[range(0x10..0x20)]
+    Reserved(u8),
+    #[range(proto::MIN_USER..proto::MAX_USER)]
+    User(u8),
+    #[range(proto::MAX_USER..)]
+    Private(u8),
+}
+
+#[test]
+fn const_expr() {
+    assert_eq!(
+        Message::ranges(),
+        &[1..=1, 2..=2, 16..=31, 0x80..=0xef, 0xf0..=0xff]
+    );
+    assert_eq!(u8::from(Message::Disconnect), 2);
+    assert_eq!(Message::try_from(1), Ok(Message::Connect));
+    assert_eq!(Message::try_from(2), Ok(Message::Disconnect));
+    assert_eq!(Message::try_from(0x10), Ok(Message::Reserved(0x10)));
+    assert_eq!(Message::try_from(0x80), Ok(Message::User(0x80)));
+    assert_eq!(Message::try_from(0xef), Ok(Message::User(0xef)));
+    assert_eq!(Message::try_from(0xf0), Ok(Message::Private(0xf0)));
+    assert_eq!(Message::try_from(0x7f).map_err(u8::from), Err(0x7f));
+    assert!(Message::User(0x80).is_valid());
+    assert!(!Message::User(0xf0).is_valid());
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(accessors)]
+enum OptionCode {
+    Pad = 0x00,
+    #[range(0x01..0x20, 0x40..0x60)]
+    Standard(u8),
+    #[range(0x20..0x30, 0x60..0x70)]
+    #[range(0xf0..)]
+    Reserved(u8),
+    #[range(rest)]
+    Unassigned(u8),
+}
+
+#[test]
+fn multiple_ranges() {
+    assert_eq!(
+        OptionCode::ranges(),
+        &[
+            0..=0,
+            1..=31,
+            64..=95,
+            32..=47,
+            96..=111,
+            240..=255,
+            48..=63,
+            112..=239
+        ]
+    );
+    assert_eq!(OptionCode::from(0x10), OptionCode::Standard(0x10));
+    assert_eq!(OptionCode::from(0x50), OptionCode::Standard(0x50));
+    assert_eq!(OptionCode::from(0x20), OptionCode::Reserved(0x20));
+    assert_eq!(OptionCode::from(0x65), OptionCode::Reserved(0x65));
+    assert_eq!(OptionCode::from(0xff), OptionCode::Reserved(0xff));
+    assert_eq!(OptionCode::from(0x30), OptionCode::Unassigned(0x30));
+    assert_eq!(OptionCode::from(0x70), OptionCode::Unassigned(0x70));
+
+    assert!(OptionCode::Standard(0x5f).is_valid());
+    assert!(!OptionCode::Standard(0x20).is_valid());
+    assert!(OptionCode::Reserved(0xf0).is_valid());
+    assert!(!OptionCode::Reserved(0x30).is_valid());
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(C, u8)]
+enum Ffi {
+    A = 1,
+    B = 2,
+    #[other]
+    Other(u8),
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u32, align(8))]
+enum Aligned {
+    A = 1,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u16, C)]
+enum FfiRange {
+    #[range(..0x100)]
+    Low(u16),
+    #[range(0x100..)]
+    High(u16),
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[cfg_attr(all(), repr(u16))]
+enum CfgRepr {
+    A = 0x1234,
+}
+
+#[test]
+fn repr() {
+    assert_eq!(u8::from(Ffi::B), 2);
+    assert_eq!(Ffi::from(1), Ffi::A);
+    assert_eq!(Ffi::from(3), Ffi::Other(3));
+    assert_eq!(u32::from(Aligned::A), 1);
+    assert_eq!(core::mem::align_of::<Aligned>(), 8);
+    assert_eq!(FfiRange::from(0x100u16), FfiRange::High(0x100));
+    assert_eq!(u16::from(CfgRepr::A), 0x1234);
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[inttype(type = u16)]
+enum NoRepr {
+    A,
+    B = 0x200,
+    C,
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+#[inttype(type = u32)]
+enum Wider {
+    A = 1,
+    #[default]
+    B = 0xff,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[inttype(type = u16)]
+enum WireRange {
+    #[range(..0x100)]
+    Short(u16),
+    #[range(0x100..)]
+    Long(u16),
+}
+
+#[test]
+fn type_option() {
+    assert_eq!(u16::from(NoRepr::A), 0);
+    assert_eq!(u16::from(NoRepr::B), 0x200);
+    assert_eq!(u16::from(NoRepr::C), 0x201);
+    assert_eq!(NoRepr::try_from(0x201), Ok(NoRepr::C));
+    assert_eq!(NoRepr::try_from(1).map_err(u16::from), Err(1));
+    assert_eq!(
+        NoRepr::try_from(1).unwrap_err().ranges(),
+        &[0..=0, 0x200..=0x200, 0x201..=0x201]
+    );
+
+    assert_eq!(core::mem::size_of::<Wider>(), 1);
+    assert_eq!(u32::from(Wider::B), 0xff);
+    assert_eq!(Wider::from(1), Wider::A);
+    assert_eq!(Wider::from(0x1ff), Wider::B);
+
+    assert_eq!(WireRange::from(0x1234), WireRange::Long(0x1234));
+    assert_eq!(u16::from(WireRange::Short(3)), 3);
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u16)]
+enum Status {
+    Ok = 200,
+    Err = 500,
+}
+
+#[test]
+fn shadowing_variant_names() {
+    assert_eq!(Status::try_from(200), Ok(Status::Ok));
+    assert_eq!(Status::try_from(500), Ok(Status::Err));
+    assert_eq!(Status::try_from(404).map_err(u16::from), Err(404));
+}
+
+/// Named like the generic parameters of the generated code
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+enum T {
+    A = 1,
+    B = 2,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+enum I {
+    A = 0,
+    #[range(0x10..0x20)]
+    B(u8),
+}
+
+#[test]
+fn shadowing_generic_names() {
+    assert_eq!(T::try_from(2), Ok(T::B));
+    let err = T::try_from(3).unwrap_err();
+    assert_eq!(err.value(), 3);
+    assert_eq!(err.ranges(), &[1..=1, 2..=2]);
+
+    let decoded = I::decode_iter([0, 0x10, 0x20]).collect::<Vec<_>>();
+    assert_eq!(decoded[..2], [Ok(I::A), Ok(I::B(0x10))]);
+    assert_eq!(decoded[2].as_ref().unwrap_err().value(), 0x20);
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[inttype(type = ::core::primitive::u8)]
+enum QualifiedType {
+    #[range(..=0x7f)]
+    Ascii(u8),
+    #[range(0x80..)]
+    High(core::primitive::u8),
+}
+
+#[test]
+fn qualified_type() {
+    assert_eq!(QualifiedType::from(0x41), QualifiedType::Ascii(0x41));
+    assert_eq!(QualifiedType::from(0xff), QualifiedType::High(0xff));
+    assert_eq!(QualifiedType::ranges(), &[0..=127, 128..=255]);
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(usize)]
+enum Index {
+    #[range(..0x100)]
+    Small(usize),
+    #[range(0x100..)]
+    Large(usize),
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(isize)]
+enum Offset {
+    Zero = 0,
+    #[range(..0)]
+    Backward(isize),
+    #[range(1..)]
+    Forward(isize),
+}
+
+#[test]
+fn pointer_sized() {
+    assert_eq!(Index::ranges(), &[0..=0xff, 0x100..=usize::MAX]);
+    assert_eq!(Index::from(usize::MAX), Index::Large(usize::MAX));
+    assert_eq!(Offset::ranges(), &[0..=0, isize::MIN..=-1, 1..=isize::MAX]);
+    assert_eq!(Offset::from(isize::MIN), Offset::Backward(isize::MIN));
+    assert_eq!(Offset::from(isize::MAX), Offset::Forward(isize::MAX));
+}
+
+fn round_trip<E: IntEnum>(value: E::Repr) -> Option<E::Repr> {
+    E::from_repr(value).map(E::to_repr)
+}
+
+#[test]
+fn traits() {
+    assert_eq!(round_trip::<Test>(255), Some(255));
+    assert_eq!(round_trip::<Test>(0), None);
+    assert_eq!(round_trip::<Opcode>(0x42), Some(0x42));
+    assert_eq!(round_trip::<Test2>(0x42), Some(0x42));
+    assert_eq!(round_trip::<Test4>(255), None);
+    assert_eq!(round_trip::<Offset>(-1), Some(-1));
+
+    assert_eq!(Test::VARIANT_COUNT, 1);
+    assert_eq!(Opcode::VARIANT_COUNT, 5);
+    assert_eq!(<Opcode as IntEnum>::is_valid(&Opcode::Unknown(0x42)), true);
+    assert_eq!(<Opcode as IntEnum>::is_valid(&Opcode::Unknown(0x10)), false);
+    assert_eq!(<Test2 as IntEnum>::is_valid(&Test2::B(16)), false);
+    assert_eq!(<Test2 as IntRangeEnum>::ranges(), Test2::ranges());
+}
+
+const PUSH: Opcode = match Opcode::from_repr(0x10) {
+    Some(op) => op,
+    None => panic!(),
+};
+const TABLE: [Option<Test2>; 3] = [
+    Test2::from_repr(0),
+    Test2::from_repr(1),
+    Test2::from_repr(16),
+];
+
+#[test]
+fn const_fn() {
+    assert_eq!(PUSH, Opcode::Push);
+    assert_eq!(Opcode::POP_VALUE, 0x11);
+    assert_eq!(Opcode::HALT_VALUE, 0xff);
+    assert_eq!(Opcode::from_repr(0x42), Some(Opcode::Unknown(0x42)));
+    assert_eq!(
+        TABLE,
+        [Some(Test2::A), Some(Test2::B(1)), Some(Test2::C(16))]
+    );
+    assert_eq!(Test2::A_VALUE, 0);
+    assert_eq!(Test::from_repr(0), None);
+    assert_eq!(Status::ERR_VALUE, 500);
+
+    const RAW: u32 = Wider::B.to_repr();
+    let name = match RAW {
+        Wider::A_VALUE => "a",
+        Wider::B_VALUE => "b",
+        _ => "?",
+    };
+    assert_eq!(name, "b");
+}
+
+#[test]
+fn enumerate() {
+    assert_eq!(
+        Opcode::VARIANTS,
+        &[Opcode::Nop, Opcode::Push, Opcode::Pop, Opcode::Halt]
+    );
+    assert_eq!(Opcode::VARIANT_COUNT, 5);
+    assert_eq!(Opcode::values().count(), 256);
+    assert_eq!(Status::VARIANTS.len(), Status::VARIANT_COUNT);
+    assert_eq!(Status::values().collect::<Vec<_>>(), [200, 500]);
+    assert_eq!(Test4::VARIANT_COUNT, 2);
+    assert_eq!(Test4::values().count(), 255);
+    assert_eq!(
+        OptionCode::values().count(),
+        OptionCode::ranges()
+            .iter()
+            .map(|r| r.clone().count())
+            .sum::<usize>()
+    );
+
+    for value in Test2::values() {
+        assert_eq!(Test2::from_repr(value).map(u8::from), Some(value));
+    }
+    for value in <Sign as IntEnum>::values() {
+        assert_eq!(Sign::try_from(value).map(i8::from), Ok(value));
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+#[inttype(display, from_str, case_insensitive)]
+enum Method {
+    Get = 1,
+    #[name = "POST"]
+    Post = 2,
+    #[other]
+    Extension(u8),
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i8)]
+#[inttype(display)]
+#[inttype(from_str)]
+enum Level {
+    Zero = 0,
+    #[range(1..10)]
+    Low(i8),
+    #[name = "Below"]
+    #[range(..0)]
+    Negative(i8),
+}
+
+#[test]
+fn strings() {
+    assert_eq!(Method::Get.name(), "Get");
+    assert_eq!(Method::Extension(7).name(), "Extension");
+    assert_eq!(Method::Post.to_string(), "POST");
+    assert_eq!(format!("{:>5}", Method::Get), "  Get");
+    assert_eq!(Method::Extension(7).to_string(), "Extension(7)");
+    assert_eq!(format!("{:>14}", Method::Extension(7)), "  Extension(7)");
+    assert_eq!(format!("{:*^15}", Method::Extension(7)), "*Extension(7)**");
+    assert_eq!(format!("{:<13}|", Method::Extension(7)), "Extension(7) |");
+    assert_eq!(format!("{:6.3}|", Method::Extension(7)), "Ext   |");
+    assert_eq!(format!("{:4}", Method::Extension(7)), "Extension(7)");
+    assert_eq!("get".parse(), Ok(Method::Get));
+    assert_eq!("Post".parse(), Ok(Method::Post));
+    assert_eq!("0x02".parse(), Ok(Method::Post));
+    assert_eq!("0b111".parse(), Ok(Method::Extension(7)));
+    assert_eq!("extension(7)".parse(), Ok(Method::Extension(7)));
+    assert!("Extension(1)".parse::<Method>().is_err());
+    assert!("256".parse::<Method>().is_err());
+
+    assert_eq!(Level::Low(3).to_string(), "Low(3)");
+    assert_eq!(Level::Negative(-3).to_string(), "Below(-3)");
+    assert_eq!(Level::Zero.to_string(), "Zero");
+    assert_eq!("Below(-0x10)".parse(), Ok(Level::Negative(-16)));
+    assert_eq!("-5".parse(), Ok(Level::Negative(-5)));
+    assert_eq!("Low(9)".parse(), Ok(Level::Low(9)));
+
+    let err = "low(9)".parse::<Level>().unwrap_err();
+    assert_eq!(err.type_name(), "Level");
+    assert_eq!(err.to_string(), "no `Level` variant has this name or value");
+    assert!("Low(10)".parse::<Level>().is_err());
+    assert!("10".parse::<Level>().is_err());
+    assert!("Negative(-1)".parse::<Level>().is_err());
+}
+
+#[derive(Debug, PartialEq, Eq, Default, IntType)]
+#[repr(u8)]
+enum Mode {
+    #[default]
+    Idle = 0,
+    Run = 1,
+    #[fallback]
+    Invalid = 0xff,
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(u8)]
+#[inttype(default)]
+enum Parity {
+    None = 0,
+    Odd = 1,
+    #[fallback]
+    Even = 2,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(default)]
+enum Packet {
+    #[fallback]
+    Malformed = 0,
+    #[range(1..=MAX_SHORT)]
+    Short(u8),
+    #[range(0x80..0xf0)]
+    Long(u8),
+}
+
+const MAX_SHORT: u8 = 0x3f;
+
+#[test]
+fn fallback() {
+    assert_eq!(Mode::default(), Mode::Idle);
+    assert_eq!(Mode::from(1), Mode::Run);
+    assert_eq!(Mode::from(7), Mode::Invalid);
+    assert_eq!(Mode::from_repr(7), Some(Mode::Invalid));
+
+    assert_eq!(Parity::default(), Parity::Even);
+    assert_eq!(Parity::from(3), Parity::Even);
+
+    assert_eq!(Packet::default(), Packet::Malformed);
+    assert_eq!(Packet::from(0x3f), Packet::Short(0x3f));
+    assert_eq!(Packet::from(0x40), Packet::Malformed);
+    assert_eq!(Packet::from(0x90), Packet::Long(0x90));
+    assert_eq!(Packet::from(0xff), Packet::Malformed);
+    assert_eq!(Packet::from_repr(0xff), Some(Packet::Malformed));
+    assert_eq!(u8::from(Packet::Malformed), 0);
+    assert_eq!(
+        Packet::ranges(),
+        &[1..=0x3f, 0x80..=0xef, 0..=0, 0x40..=0x7f, 0xf0..=0xff]
+    );
+    let mut values: Vec<u8> = Packet::values().collect();
+    values.sort();
+    assert!(values.into_iter().eq(0..=u8::MAX));
+    assert!((0..=u8::MAX).all(|n| Packet::from_repr(n).is_some()));
+}
+
+#[derive(Debug, PartialEq, Eq, IntType)]
+#[repr(i16)]
+#[inttype(int_conversions)]
+enum Delta {
+    Back = -1,
+    Stay = 0,
+    Forward = 1,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(int_conversions)]
+enum Port {
+    #[range(..0x80)]
+    Low(u8),
+    #[range(0x80..)]
+    High(u8),
+}
+
+#[test]
+fn int_conversions() {
+    assert_eq!(i64::from(Delta::Back), -1);
+    assert_eq!(isize::from(Delta::Forward), 1);
+    assert_eq!(i128::from(Delta::Stay), 0);
+
+    assert_eq!(Delta::try_from(-1i64), Ok(Delta::Back));
+    assert_eq!(Delta::try_from(1u8), Ok(Delta::Forward));
+    assert_eq!(Delta::try_from(0usize), Ok(Delta::Stay));
+    assert_eq!(Delta::try_from(-1i16), Ok(Delta::Back));
+
+    let err = Delta::try_from(2u64).unwrap_err();
+    assert_eq!(err.value(), 2u64);
+    assert!(!err.is_out_of_range());
+    assert_eq!(
+        err.to_string(),
+        "invalid value `2` for `Delta`, expected one of: -1, 0, 1"
+    );
+    let err = Delta::try_from(0x10000i32).unwrap_err();
+    assert!(err.is_out_of_range());
+    assert_eq!(
+        err.to_string(),
+        "invalid value `65536` for `Delta`, out of the range of `i16`"
+    );
+    assert!(Delta::try_from(u128::MAX).unwrap_err().is_out_of_range());
+
+    // `From<u8>` is total, so only the wider and signed types can fail
+    assert_eq!(u16::from(Port::High(0x90)), 0x90);
+    assert_eq!(i16::from(Port::Low(1)), 1);
+    assert_eq!(Port::try_from(0x90u32), Ok(Port::High(0x90)));
+    assert!(Port::try_from(-1i8).unwrap_err().is_out_of_range());
+    assert!(Port::try_from(0x100u16).unwrap_err().is_out_of_range());
+}
+
+#[test]
+fn bytes() {
+    assert_eq!(Wider::from_be_bytes([0, 0, 0, 1]), Some(Wider::A));
+    assert_eq!(Wider::B.to_le_bytes(), [0xff, 0, 0, 0]);
+    assert_eq!(Test::from_le_bytes([0xff]), Some(Test::Hello));
+    assert_eq!(Test::from_ne_bytes([0]), None);
+    assert_eq!(Temperature::Freezing(-2).to_be_bytes(), [0xff, 0xfe]);
+
+    assert_eq!(Status::try_from(&[0x01, 0xf4][..]), Ok(Status::Err));
+    assert_eq!(
+        Status::try_from(&[0x01][..]),
+        Err(FromBytesError::Length {
+            expected: 2,
+            found: 1
+        })
+    );
+    let Err(FromBytesError::Value(err)) = Status::try_from(&[0, 0][..]) else {
+        unreachable!()
+    };
+    assert_eq!(err.value(), 0);
+    assert_eq!(Test2::try_from(&[0x10][..]), Ok(Test2::C(0x10)));
+    assert_eq!(
+        FromBytesError::<TryFromTestError>::Length {
+            expected: 1,
+            found: 3
+        }
+        .to_string(),
+        "expected 1 bytes, found 3"
+    );
+
+    const DECODED: Option<Test2> = Test2::from_be_bytes([1]);
+    assert_eq!(DECODED, Some(Test2::B(1)));
+}
+
+/// Without `#[inttype(accessors)]` neither `Valid` nor a `value()` of its own clash
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+enum Validity {
+    Valid = 0,
+    #[range(1..)]
+    Invalid(u8),
+}
+
+impl Validity {
+    fn value(&self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::Invalid(_) => "invalid",
+        }
+    }
+}
+
+#[test]
+fn accessors() {
+    assert_eq!(Test2::new_b(15), Some(Test2::B(15)));
+    assert_eq!(Test2::new_b(16), None);
+    assert_eq!(Test2::new_c(16), Some(Test2::C(16)));
+    assert!(Test2::A.is_a());
+    assert!(Test2::B(1).is_b());
+    assert!(!Test2::B(1).is_c());
+    assert_eq!(Test2::C(0x20).value(), 0x20);
+    assert_eq!(Test2::A.value(), 0);
+    assert_eq!(Test2::A.range(), 0..=0);
+    assert_eq!(Test2::B(3).range(), 1..=15);
+    // invalid values still name the variant's range
+    assert_eq!(Test2::B(16).range(), 1..=15);
+
+    assert_eq!(
+        OptionCode::new_reserved(0x65),
+        Some(OptionCode::Reserved(0x65))
+    );
+    assert_eq!(OptionCode::new_reserved(0x70), None);
+    assert_eq!(OptionCode::Reserved(0x65).range(), 0x60..=0x6f);
+    assert_eq!(OptionCode::Reserved(0xf5).range(), 0xf0..=0xff);
+    assert_eq!(OptionCode::Standard(0x45).range(), 0x40..=0x5f);
+    assert_eq!(OptionCode::Unassigned(0x35).range(), 0x30..=0x3f);
+    assert_eq!(OptionCode::Pad.range(), 0..=0);
+
+    assert_eq!(
+        Registry::new_unassigned(0x05),
+        Some(Registry::Unassigned(0x05))
+    );
+    assert_eq!(Registry::new_unassigned(0x10), None);
+    assert_eq!(Registry::Unassigned(0x40).range(), 0x20..=0x7f);
+    assert_eq!(Registry::Unassigned(0x90).range(), 0x81..=0xff);
+    assert_eq!(Registry::B(0x11).range(), 0x10..=0x1f);
+    assert_eq!(Registry::C.range(), 0x80..=0x80);
+
+    assert_eq!(Message::new_user(0x80), Some(Message::User(0x80)));
+    assert_eq!(Message::new_user(0x7f), None);
+    assert_eq!(Message::User(0x80).range(), 0x80..=0xef);
+    assert_eq!(Message::Disconnect.range(), 2..=2);
+    assert!(Temperature::Freezing(-1).is_freezing());
+    assert!(Temperature::TooCold(-100).is_too_cold());
+
+    assert_eq!(Validity::from(0).value(), "valid");
+    assert_eq!(Validity::from(3).value(), "invalid");
+    assert!(Validity::Valid.is_valid());
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(kind)]
+enum Frame {
+    /// An empty frame
+    Empty = 0,
+    #[range(1..0x40, 0xc0..)]
+    Control(u8),
+    #[range(rest)]
+    Data(u8),
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(kind = Class)]
+enum Classified {
+    #[range(..0x80)]
+    Low(u8),
+    #[range(0x80..)]
+    High(u8),
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(kind)]
+enum Signal {
+    #[fallback]
+    Noise = 0,
+    #[range(0x10..0x20)]
+    Tone(u8),
+    #[range(0xf0..)]
+    Pulse(u8),
+}
+
+#[test]
+fn kind() {
+    use std::collections::HashMap;
+
+    assert_eq!(Frame::Control(1).kind(), FrameKind::Control);
+    assert_eq!(FrameKind::from(&Frame::Data(0x50)), FrameKind::Data);
+    assert_eq!(FrameKind::Empty.range(), 0..=0);
+    assert_eq!(FrameKind::Control.ranges(), &[1..=0x3f, 0xc0..=0xff]);
+    assert_eq!(FrameKind::Data.ranges(), &[0x40..=0xbf]);
+    assert!(FrameKind::Control.contains(0xc0));
+    assert!(!FrameKind::Control.contains(0x40));
+    assert_eq!(
+        FrameKind::VARIANTS,
+        &[FrameKind::Empty, FrameKind::Control, FrameKind::Data]
+    );
+
+    let mut counts = HashMap::new();
+    for value in 0..=u8::MAX {
+        *counts.entry(Frame::from(value).kind()).or_insert(0) += 1;
+    }
+    assert_eq!(counts[&FrameKind::Empty], 1);
+    assert_eq!(counts[&FrameKind::Control], 0x3f + 0x40);
+    assert_eq!(counts[&FrameKind::Data], 0x80);
+
+    assert_eq!(Classified::High(0x80).kind(), Class::High);
+    assert!(Class::Low < Class::High);
+
+    assert_eq!(SignalKind::Tone.ranges(), &[0x10..=0x1f]);
+    assert_eq!(SignalKind::Noise.range(), 0..=0);
+    assert_eq!(SignalKind::Noise.ranges(), &[0..=0, 1..=0x0f, 0x20..=0xef]);
+    assert_eq!(SignalKind::Pulse.ranges(), &[0xf0..=0xff]);
+    for kind in SignalKind::VARIANTS {
+        for value in 0..=u8::MAX {
+            let in_ranges = kind.ranges().iter().any(|r| r.contains(&value));
+            assert_eq!(kind.contains(value), in_ranges, "{kind:?} {value}");
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(kind, packed)]
+enum Sample {
+    Silent = 0,
+    #[range(1..0x80)]
+    Low(u8),
+    #[range(0x80..)]
+    High(u8),
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i16)]
+#[inttype(kind = ShiftKind, packed = CompactShift)]
+enum Shift {
+    Zero = 0,
+    #[range(..0)]
+    Back(i16),
+    #[range(1..1000)]
+    Forward(i16),
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u8)]
+#[inttype(kind, packed)]
+enum Lane {
+    #[fallback]
+    Closed = 0,
+    #[range(1..=4)]
+    Open(u8),
+}
+
+#[test]
+fn packed() {
+    use std::mem::size_of;
+
+    assert_eq!(size_of::<Sample>(), 2);
+    assert_eq!(size_of::<SamplePacked>(), size_of::<u8>());
+    assert_eq!(size_of::<Shift>(), 4);
+    assert_eq!(size_of::<CompactShift>(), size_of::<i16>());
+
+    let packed = SamplePacked::try_from(Sample::Low(0x10)).unwrap();
+    assert_eq!(packed.value(), 0x10);
+    assert_eq!(packed.kind(), SampleKind::Low);
+    assert_eq!(Sample::from(packed), Sample::Low(0x10));
+    assert_eq!(packed.unpack(), Sample::Low(0x10));
+    assert_eq!(u8::from(packed), 0x10);
+    assert_eq!(
+        SamplePacked::try_from(Sample::Silent).unwrap().kind(),
+        SampleKind::Silent
+    );
+    assert_eq!(
+        SamplePacked::try_from(Sample::Low(0x80)),
+        Err(Sample::Low(0x80))
+    );
+    for value in 0..=u8::MAX {
+        let packed = SamplePacked::new(value).unwrap();
+        assert_eq!(packed.unpack(), Sample::from(value));
+    }
+    assert!(SamplePacked::new(0x7f) < SamplePacked::new(0x80));
+
+    assert_eq!(CompactShift::new(1000), None);
+    assert_eq!(CompactShift::new(-5).unwrap().kind(), ShiftKind::Back);
+    assert_eq!(CompactShift::new(0).unwrap().unpack(), Shift::Zero);
+    assert_eq!(
+        Shift::from(CompactShift::new(999).unwrap()),
+        Shift::Forward(999)
+    );
+
+    let closed = LanePacked::new(9).unwrap();
+    assert_eq!(closed.value(), 0);
+    assert_eq!(closed.kind(), LaneKind::Closed);
+    assert_eq!(closed, LanePacked::new(0).unwrap());
+    assert_eq!(LanePacked::new(3).unwrap().value(), 3);
+    for value in 0..=u8::MAX {
+        let packed = LanePacked::new(value).unwrap();
+        assert_eq!(LanePacked::new(packed.value()), Some(packed));
+    }
+}
+
+/// The same enums, once with every `lookup`
+macro_rules! lookups {
+    ($($int_type: ident, $int_range: ident = $lookup: literal;)*) => {$(
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+        #[repr(i8)]
+        #[inttype(lookup = $lookup)]
+        enum $int_type {
+            Min = -128,
+            A = -100,
+            B,
+            C = -3,
+            D,
+            E,
+            F,
+            G = 10,
+            #[other]
+            Other(i8),
+            H = 20,
+            I = 30,
+            J = 40,
+            K,
+            L,
+            M = 100,
+            N = 120,
+            O,
+            Max = 127,
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, IntRange)]
+        #[repr(u8)]
+        #[inttype(lookup = $lookup)]
+        enum $int_range {
+            Nul = 0,
+            #[range(1..8, 0x20..0x30)]
+            Low(u8),
+            Tab = 9,
+            #[range(0x80..=0x80, 0xf0..)]
+            High(u8),
+            #[range(rest)]
+            Rest(u8),
+        }
+    )*};
+}
+
+lookups! {
+    MatchCodes, MatchClasses = "match";
+    TableCodes, TableClasses = "table";
+    SearchCodes, SearchClasses = "search";
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(i128)]
+#[inttype(lookup = "search")]
+enum Wide {
+    Zero = 0,
+    #[range(..0)]
+    Negative(i128),
+    #[range(1..)]
+    Positive(i128),
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(u16)]
+#[inttype(lookup = "table")]
+enum Reserved {
+    #[range(0x100..0x110)]
+    System(u16),
+    User = 0x200,
+    #[fallback]
+    Unknown = 0x300,
+}
+
+#[derive(Debug, PartialEq, Eq, IntRange)]
+#[repr(isize)]
+#[inttype(lookup = "table")]
+enum Step {
+    Stay = 0,
+    #[range(-4..0)]
+    Back(isize),
+    #[range(1..=4)]
+    Ahead(isize),
+}
+
+#[test]
+fn lookup() {
+    for value in i8::MIN..=i8::MAX {
+        let expected = MatchCodes::from(value).to_repr();
+        assert_eq!(TableCodes::from(value).to_repr(), expected);
+        assert_eq!(SearchCodes::from(value).to_repr(), expected);
+        assert_eq!(
+            matches!(TableCodes::from(value), TableCodes::Other(_)),
+            matches!(MatchCodes::from(value), MatchCodes::Other(_))
+        );
+    }
+    assert_eq!(TableCodes::from(-127), TableCodes::Other(-127));
+    assert_eq!(SearchCodes::from(-99), SearchCodes::B);
+    assert_eq!(SearchCodes::from(127), SearchCodes::Max);
+
+    for value in 0..=u8::MAX {
+        let expected = format!("{:?}", MatchClasses::from(value));
+        assert_eq!(format!("{:?}", TableClasses::from(value)), expected);
+        assert_eq!(format!("{:?}", SearchClasses::from(value)), expected);
+    }
+    assert_eq!(TableClasses::from(0x2f), TableClasses::Low(0x2f));
+    assert_eq!(SearchClasses::from(0xff), SearchClasses::High(0xff));
+    assert_eq!(SearchClasses::from(8), SearchClasses::Rest(8));
+
+    assert_eq!(Wide::from(i128::MIN), Wide::Negative(i128::MIN));
+    assert_eq!(Wide::from(-1), Wide::Negative(-1));
+    assert_eq!(Wide::from(0), Wide::Zero);
+    assert_eq!(Wide::from(i128::MAX), Wide::Positive(i128::MAX));
+
+    assert_eq!(Reserved::from(0xff), Reserved::Unknown);
+    assert_eq!(Reserved::from(0x10f), Reserved::System(0x10f));
+    assert_eq!(Reserved::from(0x110), Reserved::Unknown);
+    assert_eq!(Reserved::from(0x200), Reserved::User);
+    assert_eq!(Reserved::from(0x300), Reserved::Unknown);
+    assert_eq!(Reserved::from(u16::MAX), Reserved::Unknown);
+    assert_eq!(Step::try_from(-4), Ok(Step::Back(-4)));
+    assert_eq!(Step::try_from(0), Ok(Step::Stay));
+    assert_eq!(Step::try_from(4), Ok(Step::Ahead(4)));
+    assert!(Step::try_from(-5).is_err());
+    assert!(Step::try_from(isize::MIN).is_err());
+    assert!(Step::try_from(isize::MAX).is_err());
+    const SYSTEM: Option<Reserved> = Reserved::from_repr(0x100);
+    assert_eq!(SYSTEM, Some(Reserved::System(0x100)));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+#[repr(u8)]
+enum Tail {
+    A = 253,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+#[repr(i8)]
+enum Head {
+    Min = -128,
+    Next,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+#[repr(i16)]
+enum Around {
+    Zero = 0,
+    One = 1,
+    MinusTwo = -2,
+    MinusOne = -1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+#[repr(u64)]
+enum Single {
+    Only = u64::MAX,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+#[repr(u16)]
+enum Steps {
+    A = 0,
+    B,
+    #[fallback]
+    C,
+}
+
+#[test]
+fn contiguous() {
+    assert_eq!(Tail::try_from(253), Ok(Tail::A));
+    assert_eq!(Tail::try_from(255), Ok(Tail::C));
+    assert!(Tail::try_from(252).is_err());
+    assert!(Tail::try_from(0).is_err());
+    assert_eq!(Tail::from_repr(254), Some(Tail::B));
+
+    assert_eq!(Head::try_from(i8::MIN), Ok(Head::Min));
+    assert_eq!(Head::try_from(-127), Ok(Head::Next));
+    assert!(Head::try_from(-126).is_err());
+    assert!(Head::try_from(i8::MAX).is_err());
+
+    for value in i16::MIN..=i16::MAX {
+        let expected = match value {
+            -2 => Some(Around::MinusTwo),
+            -1 => Some(Around::MinusOne),
+            0 => Some(Around::Zero),
+            1 => Some(Around::One),
+            _ => None,
+        };
+        assert_eq!(Around::from_repr(value), expected);
+    }
+
+    assert_eq!(Single::try_from(u64::MAX), Ok(Single::Only));
+    assert!(Single::try_from(u64::MAX - 1).is_err());
+    assert!(Single::try_from(0).is_err());
+
+    assert_eq!(Steps::from(1), Steps::B);
+    assert_eq!(Steps::from(2), Steps::C);
+    assert_eq!(Steps::from(3), Steps::C);
+    assert_eq!(Steps::from(u16::MAX), Steps::C);
+    const B: Option<Steps> = Steps::from_repr(1);
+    assert_eq!(B, Some(Steps::B));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+#[repr(u8)]
+enum Instr {
+    Nop = 0x00,
+    Load = 0x10,
+    Store = 0x11,
+    Halt = 0xff,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntType)]
+#[repr(i8)]
+enum Trit {
+    Minus = -1,
+    Zero = 0,
+    Plus = 1,
+}
+
+#[test]
+fn slices() {
+    assert_eq!(Instr::validate_slice(&[0x00, 0x10, 0x11, 0xff]), Ok(()));
+    assert_eq!(Instr::validate_slice(&[]), Ok(()));
+    assert_eq!(Instr::validate_slice(&[0x10, 0x12, 0x13]), Err((1, 0x12)));
+    for value in 0..=u8::MAX {
+        assert_eq!(
+            Instr::validate_slice(&[value]).is_ok(),
+            Instr::from_repr(value).is_some()
+        );
+    }
+
+    let mut out = [Instr::Nop; 3];
+    assert_eq!(Instr::decode_slice(&[0xff, 0x10, 0x11], &mut out), Ok(()));
+    assert_eq!(out, [Instr::Halt, Instr::Load, Instr::Store]);
+    let mut out = [Instr::Nop; 3];
+    assert_eq!(
+        Instr::decode_slice(&[0x11, 0x01, 0x10], &mut out),
+        Err((1, 0x01))
+    );
+    assert_eq!(out, [Instr::Store, Instr::Nop, Instr::Nop]);
+
+    let decoded = Instr::decode_iter([0x10, 0x20]).collect::<Vec<_>>();
+    assert_eq!(decoded[0], Ok(Instr::Load));
+    assert_eq!(decoded[1].unwrap_err().value(), 0x20);
+
+    assert_eq!(Trit::validate_slice(&[-1, 0, 1]), Ok(()));
+    assert_eq!(Trit::validate_slice(&[1, -128]), Err((1, -128)));
+    assert_eq!(Trit::validate_slice(&[127]), Err((0, 127)));
+    let mut out = [Trit::Zero; 2];
+    assert_eq!(Trit::decode_slice(&[1, -1], &mut out), Ok(()));
+    assert_eq!(out, [Trit::Plus, Trit::Minus]);
+
+    assert_eq!(Method::validate_slice(&[1, 2]), Ok(()));
+    assert_eq!(Test4::validate_slice(&[0, 0xff]), Err((1, 0xff)));
+    assert_eq!(Test2::validate_slice(&[0, 0xff]), Ok(()));
+    let classes = TableClasses::decode_iter(0..=u8::MAX).collect::<Result<Vec<_>, _>>();
+    assert_eq!(classes.unwrap().len(), 256);
+    assert_eq!(
+        Steps::decode_iter([0, 9]).collect::<Vec<_>>(),
+        [Ok(Steps::A), Ok(Steps::C)]
+    );
+}
+
+#[test]
+#[should_panic(expected = "different lengths")]
+fn decode_slice_length() {
+    let _ = Instr::decode_slice(&[0x00], &mut [Instr::Nop; 2]);
+}